@@ -0,0 +1,147 @@
+//! Procedural macros used by the `tlogger` logging macros
+//!
+//! These are re-exported by `tlogger` as hidden items, and are not meant to be used directly.
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+/// Adds the variables a format string captures inline to its named arguments
+///
+/// Expects `callback; "format" [positional,] [name = value,] [key = value,]` and expands
+/// to `callback!(@finish "format" [positional,] [name = value, captured = captured,] [key = value,])`,
+/// so `"{user} logged in"` is passed on as if it was `"{user} logged in", user = user`.
+#[proc_macro]
+pub fn implicit_captures(input: TokenStream) -> TokenStream {
+    expand(input).unwrap_or_else(|message| {
+        format!("::core::compile_error!({message:?});")
+            .parse()
+            .unwrap()
+    })
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, &'static str> {
+    let mut tokens = input.into_iter();
+    let callback: Vec<TokenTree> = tokens
+        .by_ref()
+        .take_while(|token| !is_punct(token, ';'))
+        .collect();
+
+    let format = tokens.next().ok_or("missing format string")?;
+    let literal = literal(&format).ok_or("the format string must be a literal")?;
+    let mut groups = tokens.map(|token| match token {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Bracket => Ok(group),
+        _ => Err("expected a bracketed argument list"),
+    });
+    let positional = groups.next().ok_or("missing positional arguments")??;
+    let named = groups.next().ok_or("missing named arguments")??;
+    let fields = groups.next().ok_or("missing fields")??;
+
+    let explicit = argument_names(named.stream());
+    let mut named_arguments: Vec<TokenTree> = named.stream().into_iter().collect();
+    for name in captured_names(&literal.to_string()) {
+        if explicit.contains(&name) {
+            continue;
+        }
+        // The span of the literal resolves the variable where the format string was written
+        let ident = Ident::new(&name, literal.span());
+        named_arguments.extend([
+            TokenTree::Ident(ident.clone()),
+            TokenTree::Punct(Punct::new('=', Spacing::Alone)),
+            TokenTree::Ident(ident),
+            TokenTree::Punct(Punct::new(',', Spacing::Alone)),
+        ]);
+    }
+
+    let arguments: TokenStream = [
+        TokenTree::Punct(Punct::new('@', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("finish", Span::call_site())),
+        format,
+        TokenTree::Group(positional),
+        TokenTree::Group(Group::new(
+            Delimiter::Bracket,
+            named_arguments.into_iter().collect(),
+        )),
+        TokenTree::Group(fields),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut output = callback;
+    output.push(TokenTree::Punct(Punct::new('!', Spacing::Alone)));
+    output.push(TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        arguments,
+    )));
+    Ok(output.into_iter().collect())
+}
+
+fn is_punct(token: &TokenTree, ch: char) -> bool {
+    matches!(token, TokenTree::Punct(punct) if punct.as_char() == ch)
+}
+
+/// The literal of a token, looking through the invisible group a `$fmt:literal` may be wrapped in
+fn literal(token: &TokenTree) -> Option<Literal> {
+    match token {
+        TokenTree::Literal(literal) => Some(literal.clone()),
+        TokenTree::Group(group) if group.delimiter() == Delimiter::None => {
+            let mut inner = group.stream().into_iter();
+            match (inner.next(), inner.next()) {
+                (Some(token), None) => literal(&token),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The names of `name = value,` arguments
+fn argument_names(arguments: TokenStream) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut expect_name = true;
+    for token in arguments {
+        match token {
+            TokenTree::Ident(ident) if expect_name => {
+                names.push(ident.to_string());
+                expect_name = false;
+            }
+            token if is_punct(&token, ',') => expect_name = true,
+            _ => {}
+        }
+    }
+    names
+}
+
+/// The variables captured inline by a format string literal, such as `user` in `"{user:>8}"`
+fn captured_names(literal: &str) -> Vec<String> {
+    let contents = literal
+        .trim_start_matches('r')
+        .trim_matches('#')
+        .trim_matches('"');
+
+    let mut names = Vec::new();
+    let mut chars = contents.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+            }
+            '{' => {
+                let placeholder: String = chars.by_ref().take_while(|&ch| ch != '}').collect();
+                let argument = placeholder.split(':').next().unwrap_or_default().trim();
+                if is_identifier(argument) && !names.iter().any(|name| name == argument) {
+                    names.push(argument.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+fn is_identifier(argument: &str) -> bool {
+    argument != "_"
+        && argument
+            .chars()
+            .next()
+            .is_some_and(|ch| ch.is_alphabetic() || ch == '_')
+        && argument.chars().all(|ch| ch.is_alphanumeric() || ch == '_')
+}
@@ -0,0 +1,289 @@
+use std::{
+    cell::{Cell, RefCell},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+type Records = Arc<Mutex<Vec<LogRecord>>>;
+
+thread_local! {
+    /// The captures active on this thread, innermost last
+    static CAPTURES: RefCell<Vec<Records>> = const { RefCell::new(Vec::new()) };
+    /// The number of quiet captures active on this thread
+    static QUIET: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Passes a record to the captures active on this thread
+pub(crate) fn capture(record: &LogRecord) {
+    CAPTURES.with_borrow(|captures| {
+        for records in captures {
+            lock(records).push(record.clone());
+        }
+    });
+}
+
+/// Whether console output is suppressed on this thread
+pub(crate) fn console_quiet() -> bool {
+    QUIET.get() > 0
+}
+
+fn lock(records: &Records) -> MutexGuard<'_, Vec<LogRecord>> {
+    records.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Collects the records logged on the current thread while it is alive, for tests
+///
+/// Records are captured before any sink or level filter sees them, including the
+/// records of `TLogger` instances. Captures can be nested, each one sees the records
+/// logged while it is alive.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let capture = LogCapture::start().quiet();
+/// warn!("Memory", "Usage at **{}%**", 85);
+///
+/// assert_logged!(Warn, title = "Memory", contains = "85%");
+/// assert_not_logged!(Error);
+/// assert_eq!(capture.records().len(), 1);
+/// ```
+pub struct LogCapture {
+    records: Records,
+    quiet: bool,
+}
+
+impl LogCapture {
+    /// Starts capturing the records logged on the current thread
+    pub fn start() -> Self {
+        let records = Records::default();
+        CAPTURES.with_borrow_mut(|captures| captures.push(Arc::clone(&records)));
+        Self {
+            records,
+            quiet: false,
+        }
+    }
+
+    /// Keeps console sinks from printing on the current thread while capturing
+    pub fn quiet(mut self) -> Self {
+        if !self.quiet {
+            QUIET.set(QUIET.get() + 1);
+            self.quiet = true;
+        }
+        self
+    }
+
+    /// The records captured so far, oldest first
+    pub fn records(&self) -> Vec<LogRecord> {
+        lock(&self.records).clone()
+    }
+
+    /// Forgets the records captured so far
+    pub fn clear(&self) {
+        lock(&self.records).clear();
+    }
+}
+
+impl Drop for LogCapture {
+    fn drop(&mut self) {
+        CAPTURES.with_borrow_mut(|captures| {
+            captures.retain(|records| !Arc::ptr_eq(records, &self.records))
+        });
+        if self.quiet {
+            QUIET.set(QUIET.get() - 1);
+        }
+    }
+}
+
+/// Runs `f` and returns the records it logged on the current thread, without
+/// printing them to the console
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let records = capture_logs(|| {
+///     info!("Server", "Starting");
+/// });
+/// assert_logged!(records => Info, title = "Server");
+/// ```
+pub fn capture_logs<F: FnOnce()>(f: F) -> Vec<LogRecord> {
+    let capture = LogCapture::start().quiet();
+    f();
+    capture.records()
+}
+
+/// The records of the innermost capture active on the current thread
+///
+/// # Panics
+/// If no capture is active on the current thread.
+#[track_caller]
+pub fn captured() -> Vec<LogRecord> {
+    CAPTURES.with_borrow(|captures| match captures.last() {
+        Some(records) => lock(records).clone(),
+        None => {
+            panic!("no log capture is active on this thread, start one with `LogCapture::start`")
+        }
+    })
+}
+
+/// A description of a record, as checked by `assert_logged!` and `assert_not_logged!`
+#[derive(Debug, Clone)]
+pub struct Expectation {
+    level: LogLevel,
+    title: Option<String>,
+    message: Option<String>,
+    contains: Option<String>,
+}
+
+impl Expectation {
+    pub fn new(level: LogLevel) -> Self {
+        Self {
+            level,
+            title: None,
+            message: None,
+            contains: None,
+        }
+    }
+
+    /// The record has exactly this title
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// The message of the record, without styling markup, is exactly this
+    pub fn message<S: Into<String>>(mut self, message: S) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// The message of the record, without styling markup, contains this
+    pub fn contains<S: Into<String>>(mut self, text: S) -> Self {
+        self.contains = Some(text.into());
+        self
+    }
+
+    pub fn matches(&self, record: &LogRecord) -> bool {
+        let message = record.plain_message();
+        record.level == self.level
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|title| record.title == *title)
+            && self
+                .message
+                .as_ref()
+                .is_none_or(|expected| message == *expected)
+            && self
+                .contains
+                .as_ref()
+                .is_none_or(|text| message.contains(text.as_str()))
+    }
+
+    /// Panics unless one of `records` matches
+    #[track_caller]
+    pub fn assert_logged(&self, records: &[LogRecord]) {
+        if !records.iter().any(|record| self.matches(record)) {
+            panic!("expected {} to be logged, {}", self, describe(records));
+        }
+    }
+
+    /// Panics if one of `records` matches
+    #[track_caller]
+    pub fn assert_not_logged(&self, records: &[LogRecord]) {
+        if records.iter().any(|record| self.matches(record)) {
+            panic!("expected {} not to be logged, {}", self, describe(records));
+        }
+    }
+}
+
+impl std::fmt::Display for Expectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a record at level {}", self.level)?;
+        if let Some(title) = &self.title {
+            write!(f, " titled {:?}", title)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, " with the message {:?}", message)?;
+        }
+        if let Some(text) = &self.contains {
+            write!(f, " containing {:?}", text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the captured records for an assertion failure
+fn describe(records: &[LogRecord]) -> String {
+    if records.is_empty() {
+        return "but nothing was logged".to_string();
+    }
+    let mut description = "logged were:".to_string();
+    for record in records {
+        description.push_str(&format!(
+            "\n  {} {}: {}",
+            record.level,
+            record.title,
+            record.plain_message()
+        ));
+    }
+    description
+}
+
+/// Asserts that a record was logged, see `LogCapture`
+///
+/// Takes a level followed by any of `title = ..`, `message = ..` and `contains = ..`.
+/// Checks the innermost capture active on the current thread, or the records given
+/// before `=>`.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let _capture = LogCapture::start().quiet();
+/// warn!("Memory", "Usage at {}%", 85);
+///
+/// assert_logged!(Warn, title = "Memory", contains = "85%");
+/// assert_logged!(Warn, message = "Usage at 85%");
+/// ```
+#[macro_export]
+macro_rules! assert_logged {
+    ($records:expr => $level:ident $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::capture::Expectation::new($crate::logger::LogLevel::$level)
+            $(.$key($value))*
+            .assert_logged(::std::convert::AsRef::<[$crate::record::LogRecord]>::as_ref(&$records))
+    };
+    ($level:ident $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::capture::Expectation::new($crate::logger::LogLevel::$level)
+            $(.$key($value))*
+            .assert_logged(&$crate::capture::captured())
+    };
+}
+
+/// Asserts that no matching record was logged, see `assert_logged!`
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let _capture = LogCapture::start().quiet();
+/// info!("Server", "Starting");
+///
+/// assert_not_logged!(Error);
+/// assert_not_logged!(Info, title = "Database");
+/// ```
+#[macro_export]
+macro_rules! assert_not_logged {
+    ($records:expr => $level:ident $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::capture::Expectation::new($crate::logger::LogLevel::$level)
+            $(.$key($value))*
+            .assert_not_logged(::std::convert::AsRef::<[$crate::record::LogRecord]>::as_ref(&$records))
+    };
+    ($level:ident $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::capture::Expectation::new($crate::logger::LogLevel::$level)
+            $(.$key($value))*
+            .assert_not_logged(&$crate::capture::captured())
+    };
+}
@@ -0,0 +1,86 @@
+use std::fmt;
+
+use crate::get_colors;
+
+/// Structured `key=value` fields attached to a log message
+pub type Fields = Vec<(&'static str, String)>;
+
+/// Wraps a field value so the logging macros can pick its `Display`
+/// implementation, falling back to `Debug` when there is none.
+#[doc(hidden)]
+pub struct FieldValue<'a, T: ?Sized>(pub &'a T);
+
+#[doc(hidden)]
+pub trait DisplayFieldValue {
+    fn to_field_value(&self) -> String;
+}
+
+impl<T: fmt::Display + ?Sized> DisplayFieldValue for FieldValue<'_, T> {
+    fn to_field_value(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+#[doc(hidden)]
+pub trait DebugFieldValue {
+    fn to_field_value(&self) -> String;
+}
+
+impl<T: fmt::Debug + ?Sized> DebugFieldValue for &FieldValue<'_, T> {
+    fn to_field_value(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
+
+/// Quotes a field value if it would otherwise be ambiguous in `key=value` form
+pub fn quote_field_value(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '"' || c.is_control());
+
+    if !needs_quotes {
+        return value.to_string();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Formats fields as space separated `key=value` pairs, quoting values where needed
+pub fn format_fields(fields: &[(&str, String)]) -> String {
+    fields
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, quote_field_value(value)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats fields as dimmed `key=value` pairs to be placed after a console message
+///
+/// Returns an empty string when there are no fields.
+pub fn styled_fields(fields: &[(&str, String)]) -> String {
+    if fields.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        " {}{}{}",
+        get_colors().dim,
+        format_fields(fields),
+        get_colors().reset
+    )
+}
@@ -0,0 +1,175 @@
+use std::{env, fmt, str::FromStr, sync::OnceLock};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+/// Directives for the console, read from `TLOGGER_CONSOLE` or `TLOGGER`
+static CONSOLE_DIRECTIVES: OnceLock<Directives> = OnceLock::new();
+/// Directives for log files, read from `TLOGGER_FILE` or `TLOGGER`
+static FILE_DIRECTIVES: OnceLock<Directives> = OnceLock::new();
+
+/// `RUST_LOG` style level directives, such as `warn,my_crate::db=debug,title:Database=trace`
+///
+/// Each comma separated directive is either a level on its own, the default for
+/// all records, or `target=level`. A target is a module path, matching records
+/// logged in that module or any module below it, or `title:` followed by a log title.
+/// Title directives take precedence over module directives, and the longest
+/// matching module path wins. A target without a level enables all levels, and
+/// `off` disables a target entirely.
+///
+/// When a directive matches a record, its level replaces the global level settings,
+/// such as `set_debug` and `set_console_level` for the console or the saved log
+/// levels of the global `LOGGER`. Records no directive matches fall back to those
+/// settings. A `Logger` added as a sink keeps its own levels, directives only narrow them.
+///
+/// The `TLOGGER` environment variable applies directives to the console and to log
+/// files, `TLOGGER_CONSOLE` and `TLOGGER_FILE` override it for one of the two.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let directives: Directives = "warn,my_crate::db=debug,title:Database=off".parse().unwrap();
+///
+/// let record = LogRecord::new(LogLevel::Info, "Server", "Starting");
+/// assert!(!directives.enabled(&record));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Directives {
+    default: Option<Threshold>,
+    modules: Vec<(String, Threshold)>,
+    titles: Vec<(String, Threshold)>,
+}
+
+/// The least severe level let through, `None` letting nothing through
+type Threshold = Option<LogLevel>;
+
+/// A directive that couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectiveError(String);
+
+impl fmt::Display for DirectiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid log directive `{}`", self.0)
+    }
+}
+
+impl std::error::Error for DirectiveError {}
+
+fn parse_threshold(level: &str) -> Option<Threshold> {
+    if level.eq_ignore_ascii_case("off") {
+        return Some(None);
+    }
+    level.parse().ok().map(Some)
+}
+
+impl FromStr for Directives {
+    type Err = DirectiveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut directives = Directives::default();
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let invalid = || DirectiveError(directive.to_string());
+
+            let (target, threshold) = match directive.split_once('=') {
+                Some((target, level)) => (
+                    target.trim(),
+                    parse_threshold(level.trim()).ok_or_else(invalid)?,
+                ),
+                None => match parse_threshold(directive) {
+                    Some(threshold) => {
+                        directives.default = Some(threshold);
+                        continue;
+                    }
+                    None => (directive, Some(LogLevel::Trace)),
+                },
+            };
+
+            match target.strip_prefix("title:") {
+                Some(title) if !title.is_empty() => {
+                    directives.titles.push((title.to_string(), threshold))
+                }
+                Some(_) => return Err(invalid()),
+                None if target.is_empty() || target.contains(char::is_whitespace) => {
+                    return Err(invalid())
+                }
+                None => directives.modules.push((target.to_string(), threshold)),
+            }
+        }
+        Ok(directives)
+    }
+}
+
+impl Directives {
+    /// Whether the directives let the record through
+    pub fn enabled(&self, record: &LogRecord) -> bool {
+        self.enabled_or(record, || true)
+    }
+
+    /// Whether the directives let the record through, deciding with `fallback` when
+    /// no directive matches it
+    pub(crate) fn enabled_or(&self, record: &LogRecord, fallback: impl FnOnce() -> bool) -> bool {
+        let threshold = self
+            .titles
+            .iter()
+            .rev()
+            .find(|(title, _)| *title == record.title)
+            .map(|(_, threshold)| threshold)
+            .or_else(|| self.module_threshold(record))
+            .or(self.default.as_ref());
+
+        match threshold {
+            None => fallback(),
+            Some(None) => false,
+            Some(Some(min)) => record.level >= *min,
+        }
+    }
+
+    /// Whether any directive lets records of this level through
+    pub(crate) fn may_enable(&self, level: &LogLevel) -> bool {
+        self.default
+            .iter()
+            .chain(self.modules.iter().map(|(_, threshold)| threshold))
+            .chain(self.titles.iter().map(|(_, threshold)| threshold))
+            .any(|threshold| threshold.is_some_and(|min| *level >= min))
+    }
+
+    /// The threshold of the longest module path the record was logged in
+    fn module_threshold(&self, record: &LogRecord) -> Option<&Threshold> {
+        let module_path = record.callsite?.module_path;
+        self.modules
+            .iter()
+            .filter(|(module, _)| {
+                module_path
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, threshold)| threshold)
+    }
+
+    /// Reads directives from `var`, falling back to `TLOGGER`
+    ///
+    /// Invalid directives are reported on stderr and ignored altogether.
+    fn from_env(var: &str) -> Self {
+        let Some((var, value)) = [var, "TLOGGER"]
+            .into_iter()
+            .find_map(|var| env::var(var).ok().map(|value| (var, value)))
+        else {
+            return Directives::default();
+        };
+        value.parse().unwrap_or_else(|e| {
+            eprintln!("Ignoring the {var} log directives: {e}");
+            Directives::default()
+        })
+    }
+}
+
+/// The directives for the console, see [`Directives`]
+pub fn console_directives() -> &'static Directives {
+    CONSOLE_DIRECTIVES.get_or_init(|| Directives::from_env("TLOGGER_CONSOLE"))
+}
+
+/// The directives for log files, see [`Directives`]
+pub fn file_directives() -> &'static Directives {
+    FILE_DIRECTIVES.get_or_init(|| Directives::from_env("TLOGGER_FILE"))
+}
@@ -0,0 +1,138 @@
+use chrono::SecondsFormat;
+
+use crate::{
+    fields::{format_fields, quote_field_value},
+    record::LogRecord,
+    timezone::Timezone,
+};
+
+/// The format records are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// `WARN    12:34:56.791+01:00 │ Memory Usage at 85% usage=85`
+    #[default]
+    Text,
+    /// One JSON object per line
+    ///
+    /// `{"timestamp":"2024-11-20T10:15:02.123+01:00","level":"warn","title":"Memory","message":"Usage at 85%","fields":{"usage":"85"}}`
+    Json,
+    /// `key=value` pairs, with values quoted where needed
+    ///
+    /// `ts=2024-11-20T10:15:02.123+01:00 level=warn title=Memory msg="Usage at 85%" usage=85`
+    Logfmt,
+}
+
+impl LogFormat {
+    /// Formats a record as a single line, including the trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        self.format_with_timezone(record, Timezone::Local)
+    }
+
+    /// Formats a record as a single line with its timestamp in `timezone`
+    pub fn format_with_timezone(&self, record: &LogRecord, timezone: Timezone) -> String {
+        match self {
+            LogFormat::Text => format_text(record, timezone),
+            LogFormat::Json => format_json(record, timezone),
+            LogFormat::Logfmt => format_logfmt(record, timezone),
+        }
+    }
+
+    /// The extension used for log files in this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            LogFormat::Text | LogFormat::Logfmt => "log",
+            LogFormat::Json => "jsonl",
+        }
+    }
+}
+
+/// Formats a record as a plain text log line
+///
+/// The line is independent of the configured symbols and colors, and has any
+/// `style_text!` markup removed. The time includes the UTC offset, the date is
+/// left to the file name. Fields are appended as `key=value` pairs.
+pub fn format_text(record: &LogRecord, timezone: Timezone) -> String {
+    let mut line = format!(
+        "{:<7} {} │ {} {}",
+        record.level.as_str().to_uppercase(),
+        timezone.convert(record.timestamp).format("%H:%M:%S%.3f%:z"),
+        record.title,
+        record.plain_message()
+    );
+    if !record.fields.is_empty() {
+        line.push(' ');
+        line.push_str(&format_fields(&record.fields));
+    }
+    line.push('\n');
+    line
+}
+
+/// Formats a record as a single line JSON object
+///
+/// The timestamp is ISO-8601 with millisecond precision and the message has any
+/// `style_text!` markup removed. Fields are kept as a separate object.
+pub fn format_json(record: &LogRecord, timezone: Timezone) -> String {
+    let mut line = format!(
+        "{{\"timestamp\":{},\"level\":{},\"title\":{},\"message\":{}",
+        json_string(
+            &timezone
+                .convert(record.timestamp)
+                .to_rfc3339_opts(SecondsFormat::Millis, false)
+        ),
+        json_string(record.level.as_str()),
+        json_string(&record.title),
+        json_string(&record.plain_message())
+    );
+    if !record.fields.is_empty() {
+        let fields = record
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}:{}", json_string(key), json_string(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        line.push_str(&format!(",\"fields\":{{{}}}", fields));
+    }
+    line.push_str("}\n");
+    line
+}
+
+/// Formats a record as a single logfmt line
+///
+/// The message has any `style_text!` markup removed, and the title, message and
+/// field values are quoted and escaped where needed.
+pub fn format_logfmt(record: &LogRecord, timezone: Timezone) -> String {
+    let mut line = format!(
+        "ts={} level={} title={} msg={}",
+        timezone
+            .convert(record.timestamp)
+            .to_rfc3339_opts(SecondsFormat::Millis, false),
+        record.level.as_str(),
+        quote_field_value(&record.title),
+        quote_field_value(&record.plain_message())
+    );
+    if !record.fields.is_empty() {
+        line.push(' ');
+        line.push_str(&format_fields(&record.fields));
+    }
+    line.push('\n');
+    line
+}
+
+/// Quotes and escapes a string as a JSON string literal
+pub fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
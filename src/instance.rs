@@ -0,0 +1,137 @@
+use std::{io, sync::Arc};
+
+use crate::{
+    capture::capture,
+    logger::LogLevel,
+    record::LogRecord,
+    sinks::{ActiveSink, ConsoleSink, Filtered, LevelFilter, Sink},
+    styling::{Borders, Colors, Symbols, Theme},
+};
+
+/// A logger with its own theme, sinks and levels, independent of the global settings
+///
+/// The logging macros take a logger before `=>` to log to it instead of the global
+/// sinks and `LOGGER`. Useful for libraries and tests that shouldn't share the
+/// configuration of the application.
+///
+/// The console sink still follows `set_debug`, `set_console_level`, `set_console_format`
+/// and the `TLOGGER` directives.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+/// use tlogger::styling::Symbols;
+///
+/// let memory = MemorySink::new();
+/// let logger = TLogger::new()
+///     .with_symbols(Symbols {
+///         info: "[i]",
+///         ..Default::default()
+///     })
+///     .with_sink(memory.clone())
+///     .with_min_level(LogLevel::Info);
+///
+/// info!(logger => "Database", "Connected to {}", "localhost");
+/// warn_box!(logger => "Database", "Slow query");
+/// debug!(logger => "Database", "Not logged");
+///
+/// assert_eq!(memory.lines().len(), 2);
+/// ```
+#[derive(Clone)]
+pub struct TLogger {
+    theme: Theme,
+    sinks: Vec<Arc<dyn Sink>>,
+    filter: LevelFilter,
+}
+
+impl TLogger {
+    /// A logger printing records of all levels to the console, with the default theme
+    pub fn new() -> Self {
+        Self {
+            theme: Theme::default(),
+            sinks: vec![Arc::new(ConsoleSink::new())],
+            filter: LevelFilter::default(),
+        }
+    }
+
+    pub fn with_colors(mut self, colors: Colors) -> Self {
+        self.theme.colors = Arc::new(colors);
+        self
+    }
+
+    pub fn with_symbols(mut self, symbols: Symbols) -> Self {
+        self.theme.symbols = Arc::new(symbols);
+        self
+    }
+
+    pub fn with_borders(mut self, borders: Borders) -> Self {
+        self.theme.borders = Arc::new(borders);
+        self
+    }
+
+    /// Passes records to this sink as well
+    ///
+    /// A `Logger` can be added to write the records to a log directory.
+    pub fn with_sink<S: Sink + 'static>(mut self, sink: S) -> Self {
+        self.sinks.push(Arc::new(sink));
+        self
+    }
+
+    /// Removes all sinks, including the console sink every logger starts with
+    pub fn without_sinks(mut self) -> Self {
+        self.sinks.clear();
+        self
+    }
+
+    /// Whether records of this level are logged
+    pub fn enabled(&self, level: &LogLevel) -> bool {
+        self.filter.enabled(level)
+    }
+
+    /// Passes a record to the sinks of this logger, rendering it with its theme
+    ///
+    /// This is what the logging macros call when given a logger.
+    pub fn log(&self, record: &LogRecord) {
+        capture(record);
+        if !self.enabled(&record.level) {
+            return;
+        }
+
+        self.theme.scope(|| {
+            for sink in &self.sinks {
+                if sink.enabled(&record.level) {
+                    let _active = ActiveSink::enter(&**sink);
+                    if let Err(e) = sink.log(record) {
+                        eprintln!("Error writing to log sink: {e}");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Writes out anything the sinks have buffered
+    ///
+    /// Every sink is flushed, the first error is returned.
+    pub fn flush(&self) -> io::Result<()> {
+        let mut result = Ok(());
+        for sink in &self.sinks {
+            let _active = ActiveSink::enter(&**sink);
+            if let Err(e) = sink.flush() {
+                result = result.and(Err(e));
+            }
+        }
+        result
+    }
+}
+
+impl Filtered for TLogger {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.filter
+    }
+}
+
+impl Default for TLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,25 +1,70 @@
-use std::sync::OnceLock;
-
-use logger::Logger;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, LazyLock, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError,
+};
+
+use fields::styled_fields;
+use format::LogFormat;
+use logger::{LogLevel, Logger};
 use prelude::strip_ansi_codes;
+use record::LogRecord;
+use sinks::{ActiveSink, ConsoleSink, Sink};
 use styling::*;
-
+use timezone::Timezone;
+
+#[doc(hidden)]
+pub use tlogger_macros::implicit_captures as __implicit_captures;
+
+pub mod capture;
+pub mod fields;
+pub mod filter;
+pub mod format;
+pub mod instance;
+#[cfg(feature = "log")]
+pub mod log_facade;
 pub mod logger;
 pub mod opts;
 pub mod prelude;
+pub mod record;
+pub mod sinks;
 pub mod styling;
 mod tests;
+pub mod timezone;
+#[cfg(feature = "tracing")]
+pub mod tracing_layer;
 
 /// No log saving is used by default
 ///
 /// This is the global logger, if enabled
-/// all logs will be written into a daily log file at the provided path
-pub static mut LOGGER: OnceLock<Logger> = OnceLock::new();
+/// all logs will be written into a log file per interval at the provided path.
+/// Set up with `init_logger`, which can be called again to replace it.
+pub static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+/// Records are printed to the console by default
+///
+/// Every record logged is passed to each of these sinks, in addition to the global `LOGGER`.
+pub static SINKS: LazyLock<RwLock<Vec<Box<dyn Sink>>>> =
+    LazyLock::new(|| RwLock::new(vec![Box::new(ConsoleSink::new())]));
 /// DEBUG is enabled by default
 ///
 /// If you are ready to ship to production, setting this to false will prevent debug messages from being printed
 /// to the console, but will still be logged to a log file.
-pub static DEBUG: OnceLock<bool> = OnceLock::new();
+pub static DEBUG: AtomicBool = AtomicBool::new(true);
+
+/// All levels are printed to the console by default
+///
+/// Only records of this level or a more severe one are printed.
+pub static CONSOLE_LEVEL: RwLock<LogLevel> = RwLock::new(LogLevel::Trace);
+
+/// The console uses the styled tlogger layout by default
+///
+/// If set, records are printed to the console in this format instead, for example
+/// `LogFormat::Logfmt` for tooling that reads logfmt from stdout.
+pub static CONSOLE_FORMAT: RwLock<Option<LogFormat>> = RwLock::new(None);
+
+/// Timestamps use the local time zone by default
+///
+/// Console timestamps are shown in this time zone.
+pub static TIMEZONE: RwLock<Timezone> = RwLock::new(Timezone::Local);
 
 /// Text Styling is on by default
 ///
@@ -27,51 +72,197 @@ pub static DEBUG: OnceLock<bool> = OnceLock::new();
 ///
 /// # Example usage of a Test Style
 /// ```rust
+/// use tlogger::prelude::*;
+///
 /// fn printme() {
 ///     info!("Title", "THis message _here has underlines_");
 /// }
 /// ```
 ///
 /// if toggled to off these styles will be ignored.
-pub static mut TEXT_STYLING: OnceLock<bool> = OnceLock::new();
+pub static TEXT_STYLING: AtomicBool = AtomicBool::new(true);
+
+/// Locks a global for reading, carrying on with the data of a thread that panicked while holding it
+pub(crate) fn read<T: ?Sized>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Locks a global for reading unless that would block, carrying on with the data of a
+/// thread that panicked while holding it
+pub(crate) fn try_read<T: ?Sized>(lock: &RwLock<T>) -> Option<RwLockReadGuard<'_, T>> {
+    match lock.try_read() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::Poisoned(e)) => Some(e.into_inner()),
+        Err(TryLockError::WouldBlock) => None,
+    }
+}
+
+/// Locks a global for writing, carrying on with the data of a thread that panicked while holding it
+pub(crate) fn write<T: ?Sized>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| e.into_inner())
+}
 
 #[inline]
 pub fn text_styling_on() {
-    unsafe { TEXT_STYLING.set(true).unwrap() };
+    TEXT_STYLING.store(true, Ordering::Relaxed);
 }
 
 #[inline]
 pub fn text_styling_off() {
-    unsafe { TEXT_STYLING.set(false).unwrap() };
+    TEXT_STYLING.store(false, Ordering::Relaxed);
 }
 
 #[inline]
-pub fn get_text_styling() -> &'static bool {
-    unsafe { TEXT_STYLING.get_or_init(|| true) }
+pub fn get_text_styling() -> bool {
+    TEXT_STYLING.load(Ordering::Relaxed)
 }
 
 /// Get the current timestamp in the format HH:MM:SS.SSS
 #[inline]
 pub fn get_timestamp() -> String {
-    chrono::Local::now().format("%H:%M:%S%.3f").to_string()
+    get_timezone().now().format("%H:%M:%S%.3f").to_string()
+}
+
+/// Get the time zone console timestamps are shown in
+#[inline]
+pub fn get_timezone() -> Timezone {
+    *read(&TIMEZONE)
 }
 
 /// Get the current colors
+///
+/// These are the colors of the `TLogger` rendering a record, or the global ones.
 #[inline]
-pub fn get_colors() -> &'static Colors {
-    COLORS.get_or_init(|| Colors::default())
+pub fn get_colors() -> Arc<Colors> {
+    Theme::scoped(|theme| &theme.colors).unwrap_or_else(|| Arc::clone(&read(&COLORS)))
 }
 
 /// Get the current symbols
 #[inline]
-pub fn get_symbols() -> &'static Symbols {
-    SYMBOLS.get_or_init(|| Symbols::default())
+pub fn get_symbols() -> Arc<Symbols> {
+    Theme::scoped(|theme| &theme.symbols).unwrap_or_else(|| Arc::clone(&read(&SYMBOLS)))
 }
 
 /// Get the current border characters
 #[inline]
-pub fn get_borders() -> &'static Borders {
-    BORDERS.get_or_init(|| Borders::default())
+pub fn get_borders() -> Arc<Borders> {
+    Theme::scoped(|theme| &theme.borders).unwrap_or_else(|| Arc::clone(&read(&BORDERS)))
+}
+
+/// Creates a single log line with the given symbol, timestamp, title and message
+pub fn create_log_line(
+    color: &str,
+    symbol: &str,
+    title: &str,
+    text_color: &str,
+    timestamp: &str,
+    message: &str,
+) -> String {
+    log_line(
+        color,
+        symbol,
+        title,
+        timestamp,
+        &style_text!(message, text_color),
+    )
+}
+
+/// A single log line around a message that is already styled
+fn log_line(
+    color: &str,
+    symbol: &str,
+    title: &str,
+    timestamp: &str,
+    processed_message: &str,
+) -> String {
+    format!(
+        "{}{} {} {}{}{}{} {} {}{}{}{}{} {}",
+        color,
+        symbol,
+        get_colors().reset,
+        get_colors().dim,
+        timestamp,
+        get_colors().reset,
+        get_colors().dim,
+        get_symbols().separator,
+        get_colors().reset,
+        get_colors().bold,
+        color,
+        title,
+        get_colors().reset,
+        processed_message
+    )
+}
+
+/// Renders a record the way it is shown on the console, as a single line or a box,
+/// or in the `CONSOLE_FORMAT` if one is set
+pub fn format_console(record: &LogRecord) -> String {
+    if let Some(format) = *read(&CONSOLE_FORMAT) {
+        return format.format_with_timezone(record, get_timezone());
+    }
+
+    let level = &record.level;
+
+    if record.boxed {
+        styled_box(
+            level.color(),
+            level.text_color(),
+            level.symbol(),
+            &record.title,
+            &record.message,
+            &record.fields,
+            75,
+            record.raw,
+        )
+    } else {
+        let message = if record.raw {
+            format!(
+                "{}{}{}",
+                level.text_color(),
+                record.message,
+                get_colors().reset
+            )
+        } else {
+            style_text!(&record.message, level.text_color())
+        };
+        format!(
+            "{}{}\n",
+            log_line(
+                level.color(),
+                level.symbol(),
+                &record.title,
+                &get_timezone()
+                    .convert(record.timestamp)
+                    .format("%H:%M:%S%.3f")
+                    .to_string(),
+                &message,
+            ),
+            styled_fields(&record.fields)
+        )
+    }
+}
+
+/// Passes a record to every registered sink and writes it to the global `LOGGER`, if enabled
+///
+/// This is what all logging macros call once they have built their record.
+pub fn log_record(record: &LogRecord) {
+    capture::capture(record);
+
+    for sink in read(&SINKS).iter() {
+        if sink.enabled(&record.level) {
+            let _active = ActiveSink::enter(&**sink);
+            if let Err(e) = sink.log(record) {
+                eprintln!("Error writing to log sink: {e}");
+            }
+        }
+    }
+
+    if let Some(logger) = read(&LOGGER).as_ref() {
+        let _active = ActiveSink::enter(logger);
+        if let Err(e) = logger.log_global(record) {
+            eprintln!("Error logging to file: {e}");
+        }
+    }
 }
 
 /// Creates a perfectly formatted box with the given title and message
@@ -81,12 +272,50 @@ pub fn create_styled_box(
     symbol: &str,
     title: &str,
     message: &str,
+    width: usize,
+) -> String {
+    create_styled_box_with_fields(box_color, text_color, symbol, title, message, &[], width)
+}
+
+/// Creates a perfectly formatted box with the given title and message,
+/// followed by a key/value section for the given fields
+pub fn create_styled_box_with_fields(
+    box_color: &str,
+    text_color: &str,
+    symbol: &str,
+    title: &str,
+    message: &str,
+    fields: &[(&str, String)],
+    width: usize,
+) -> String {
+    styled_box(
+        box_color, text_color, symbol, title, message, fields, width, false,
+    )
+}
+
+/// A box around a message, which only has its `style_text!` markup applied unless `raw`
+#[allow(clippy::too_many_arguments)]
+fn styled_box(
+    box_color: &str,
+    text_color: &str,
+    symbol: &str,
+    title: &str,
+    message: &str,
+    fields: &[(&str, String)],
     mut width: usize,
+    raw: bool,
 ) -> String {
+    let style_line = |line: &str| {
+        if raw {
+            format!("{}{}{}", text_color, line, get_colors().reset)
+        } else {
+            style_text!(line, text_color)
+        }
+    };
     let mut result = String::new();
 
     // Get timestamp
-    let timestamp = chrono::Local::now().format("%H:%M:%S").to_string();
+    let timestamp = get_timezone().now().format("%H:%M:%S").to_string();
     let timestamp_display = format!("⏳ {}", timestamp);
 
     // Calculate minimum required width based on title and timestamp
@@ -119,8 +348,7 @@ pub fn create_styled_box(
     ));
 
     // Process message
-    let processed_message = style_text!(message, text_color);
-    let clean_message = strip_ansi_codes(&processed_message);
+    let clean_message = strip_ansi_codes(&style_line(message));
     let max_line_width = width.saturating_sub(4); // Account for borders and padding
 
     // Split message by explicit line breaks first
@@ -155,7 +383,7 @@ pub fn create_styled_box(
                         "{}{} {}{}{}{}{}{}\n",
                         box_color,
                         get_borders().vertical,
-                        style_text!(&current_line, text_color),
+                        style_line(&current_line),
                         get_colors().reset,
                         box_color,
                         " ".repeat(padding),
@@ -178,7 +406,7 @@ pub fn create_styled_box(
                         "{}{} {}{}{}{}{}{}\n",
                         box_color,
                         get_borders().vertical,
-                        style_text!(chunk, text_color),
+                        style_line(chunk),
                         get_colors().reset,
                         box_color,
                         " ".repeat(padding),
@@ -211,7 +439,7 @@ pub fn create_styled_box(
                         "{}{} {}{}{}{}{}{}\n",
                         box_color,
                         get_borders().vertical,
-                        style_text!(&current_line, text_color),
+                        style_line(&current_line),
                         get_colors().reset,
                         box_color,
                         " ".repeat(padding),
@@ -226,7 +454,7 @@ pub fn create_styled_box(
                     "{}{} {}{}{}{}{}{}\n",
                     box_color,
                     get_borders().vertical,
-                    style_text!(&current_line, text_color),
+                    style_line(&current_line),
                     get_colors().reset,
                     box_color,
                     " ".repeat(padding),
@@ -245,7 +473,7 @@ pub fn create_styled_box(
                         "{}{} {}{}{}{}{}{}\n",
                         box_color,
                         get_borders().vertical,
-                        style_text!(&current_line, text_color),
+                        style_line(&current_line),
                         get_colors().reset,
                         box_color,
                         " ".repeat(padding),
@@ -257,6 +485,57 @@ pub fn create_styled_box(
         }
     }
 
+    // Key/value section, separated from the message by an empty line
+    if !fields.is_empty() {
+        result.push_str(&format!(
+            "{}{} {}{}{}\n",
+            box_color,
+            get_borders().vertical,
+            " ".repeat(width.saturating_sub(4)),
+            get_borders().vertical,
+            get_colors().reset
+        ));
+
+        let key_width = fields
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+        let value_width = max_line_width.saturating_sub(key_width + 1).max(1);
+
+        for (key, value) in fields {
+            let value: Vec<char> = value.chars().collect();
+            let chunks: Vec<String> = if value.is_empty() {
+                vec![String::new()]
+            } else {
+                value
+                    .chunks(value_width)
+                    .map(|chunk| chunk.iter().collect())
+                    .collect()
+            };
+
+            for (i, chunk) in chunks.iter().enumerate() {
+                let key = if i == 0 { *key } else { "" };
+                let padding = width.saturating_sub(key_width + 1 + chunk.chars().count() + 4);
+                result.push_str(&format!(
+                    "{}{} {}{:<key_width$}{} {}{}{}{}{}{}{}\n",
+                    box_color,
+                    get_borders().vertical,
+                    get_colors().dim,
+                    key,
+                    get_colors().reset,
+                    text_color,
+                    chunk,
+                    get_colors().reset,
+                    box_color,
+                    " ".repeat(padding),
+                    get_borders().vertical,
+                    get_colors().reset
+                ));
+            }
+        }
+    }
+
     // Bottom border
     result.push_str(&format!(
         "{}{} {}{} {}\n", // Added spaces for alignment
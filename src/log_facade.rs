@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use crate::{
+    log_record,
+    logger::LogLevel,
+    record::{Callsite, LogRecord},
+};
+
+/// A [`log::Log`] implementation that turns `log` records into tlogger records.
+///
+/// The record target is used as the title, so a `log::warn!` from a dependency
+/// shows up (and is saved to the log file) just like a `warn!` of your own. The
+/// message is kept as is, `style_text!` markup is not applied to it.
+pub struct TLog;
+
+static TLOG: TLog = TLog;
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => LogLevel::Error,
+            Level::Warn => LogLevel::Warn,
+            Level::Info => LogLevel::Info,
+            Level::Debug => LogLevel::Debug,
+            Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl Log for TLog {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut entry = LogRecord::new(
+            LogLevel::from(record.level()),
+            record.target(),
+            record.args().to_string(),
+        )
+        .raw(true);
+
+        if let (Some(module_path), Some(file), Some(line)) = (
+            record.module_path_static(),
+            record.file_static(),
+            record.line(),
+        ) {
+            entry = entry.with_callsite(Callsite::new(module_path, file, line));
+        }
+
+        log_record(&entry);
+    }
+
+    fn flush(&self) {
+        _ = std::io::stdout().flush();
+        _ = std::io::stderr().flush();
+    }
+}
+
+/// Routes everything emitted through the `log` crate into tlogger
+///
+/// Only records at or below `max_level` are shown. This can only be called
+/// once per program, as `log` only allows a single logger to be installed.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// install_log_facade(log::LevelFilter::Info).unwrap();
+///
+/// log::warn!("Memory usage at {}%", 85);
+/// ```
+#[inline]
+pub fn install_log_facade(max_level: LevelFilter) -> Result<(), SetLoggerError> {
+    log::set_logger(&TLOG)?;
+    log::set_max_level(max_level);
+    Ok(())
+}
@@ -0,0 +1,201 @@
+use std::{
+    collections::VecDeque,
+    io, mem,
+    sync::{Arc, Condvar, Mutex, MutexGuard, TryLockError},
+    thread::{self, JoinHandle, ThreadId},
+};
+
+use crate::{opts::flush_at_exit, record::LogRecord};
+
+use super::{file::FileWriter, lock, LogLevel};
+
+/// What happens to a record when the queue of a background writer is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Wait until the writer thread has made room
+    #[default]
+    Block,
+    /// Drop the record being logged
+    DropNewest,
+    /// Drop the oldest queued record to make room
+    DropOldest,
+}
+
+#[derive(Default)]
+struct QueueState {
+    records: VecDeque<LogRecord>,
+    /// Records dropped since the writer thread last took records off the queue
+    dropped: u64,
+    /// Whether the writer thread is writing records it took off the queue
+    writing: bool,
+    shutdown: bool,
+}
+
+struct Queue {
+    state: Mutex<QueueState>,
+    /// Notified whenever records are queued or written, or on shutdown
+    changed: Condvar,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        lock(&self.state)
+    }
+
+    fn wait<'a>(&self, state: MutexGuard<'a, QueueState>) -> MutexGuard<'a, QueueState> {
+        self.changed.wait(state).unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Writes records to the log files on a dedicated thread, fed by a bounded queue
+pub(crate) struct BackgroundWriter {
+    queue: Arc<Queue>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    handle: Option<JoinHandle<()>>,
+    /// The writer thread, which must never wait on itself
+    thread: ThreadId,
+}
+
+impl BackgroundWriter {
+    pub(crate) fn spawn(
+        writer: Arc<Mutex<FileWriter>>,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> io::Result<Self> {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState::default()),
+            changed: Condvar::new(),
+        });
+
+        let thread_queue = Arc::clone(&queue);
+        let handle = thread::Builder::new()
+            .name("tlogger-writer".to_string())
+            .spawn(move || run(&thread_queue, &writer))?;
+        // A writer kept in the sinks is never dropped, so it is flushed at exit instead
+        flush_at_exit();
+
+        Ok(Self {
+            queue,
+            capacity: capacity.max(1),
+            overflow,
+            thread: handle.thread().id(),
+            handle: Some(handle),
+        })
+    }
+
+    /// Queues a record to be written, applying the overflow policy if the queue is full
+    pub(crate) fn send(&self, record: LogRecord) {
+        let mut state = self.queue.lock();
+        if state.records.len() >= self.capacity {
+            match self.overflow {
+                // The writer thread logs a record while writing, when it panics
+                OverflowPolicy::Block if self.on_writer_thread() => {}
+                OverflowPolicy::Block => {
+                    while state.records.len() >= self.capacity && !state.shutdown {
+                        state = self.queue.wait(state);
+                    }
+                }
+                OverflowPolicy::DropNewest => {
+                    state.dropped += 1;
+                    return;
+                }
+                OverflowPolicy::DropOldest => {
+                    state.records.pop_front();
+                    state.dropped += 1;
+                }
+            }
+        }
+        state.records.push_back(record);
+        drop(state);
+        self.queue.changed.notify_all();
+    }
+
+    /// Queues a record unless the queue is locked, making room by dropping the oldest
+    /// record if it is full
+    pub(crate) fn try_send(&self, record: LogRecord) {
+        let mut state = match self.queue.state.try_lock() {
+            Ok(state) => state,
+            Err(TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(TryLockError::WouldBlock) => return,
+        };
+        if state.records.len() >= self.capacity {
+            state.records.pop_front();
+            state.dropped += 1;
+        }
+        state.records.push_back(record);
+        drop(state);
+        self.queue.changed.notify_all();
+    }
+
+    /// Waits until every queued record has been written
+    ///
+    /// Returns right away on the writer thread itself.
+    pub(crate) fn flush(&self) {
+        if self.on_writer_thread() {
+            return;
+        }
+        let mut state = self.queue.lock();
+        while !state.records.is_empty() || state.writing {
+            state = self.queue.wait(state);
+        }
+    }
+
+    fn on_writer_thread(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+}
+
+impl Drop for BackgroundWriter {
+    /// Writes all queued records before stopping the writer thread
+    fn drop(&mut self) {
+        self.queue.lock().shutdown = true;
+        self.queue.changed.notify_all();
+        if let Some(handle) = self.handle.take() {
+            _ = handle.join();
+        }
+    }
+}
+
+/// The writer thread, writing queued records until shut down with an empty queue
+fn run(queue: &Queue, writer: &Mutex<FileWriter>) {
+    loop {
+        let (records, dropped) = {
+            let mut state = queue.lock();
+            while state.records.is_empty() && !state.shutdown {
+                state = queue.wait(state);
+            }
+            if state.records.is_empty() {
+                return;
+            }
+            state.writing = true;
+            (mem::take(&mut state.records), mem::take(&mut state.dropped))
+        };
+        // Wake up anyone blocked on a full queue
+        queue.changed.notify_all();
+
+        let mut writer = lock(writer);
+        if dropped > 0 {
+            let warning = LogRecord::new(
+                LogLevel::Warn,
+                "Logger",
+                format!("Dropped {} log records, the log queue was full", dropped),
+            );
+            if let Err(e) = writer.write(&warning) {
+                eprintln!("Error logging to file: {e}");
+            }
+        }
+        for record in records {
+            if let Err(e) = writer.write(&record) {
+                eprintln!("Error logging to file: {e}");
+            }
+        }
+        if let Err(e) = writer.flush() {
+            eprintln!("Error logging to file: {e}");
+        }
+        drop(writer);
+
+        queue.lock().writing = false;
+        queue.changed.notify_all();
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{self, Read},
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder};
+
+/// Extensions added to log files once they are compressed
+pub(crate) const COMPRESSED_EXTENSIONS: [&str; 2] = [".gz", ".zst"];
+
+/// How log files are compressed once the logger has moved on to a new file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Files are kept as they are
+    #[default]
+    None,
+    /// Files are compressed with gzip, `2024-11-20-10h-11h.log.gz`
+    Gzip,
+    /// Files are compressed with zstd, `2024-11-20-10h-11h.log.zst`
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// The extension added to compressed files
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some(".gz"),
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => Some(".zst"),
+        }
+    }
+}
+
+/// Appends an extension to the file name of `path`, keeping the existing one
+pub(crate) fn with_extension_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// Whether a file exists at `path`, either as is or compressed
+pub(crate) fn exists_in_any_form(path: &Path) -> bool {
+    path.exists()
+        || COMPRESSED_EXTENSIONS
+            .iter()
+            .any(|ext| with_extension_suffix(path, ext).exists())
+}
+
+/// Whether the file name ends in one of the compressed extensions
+pub(crate) fn is_compressed(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    COMPRESSED_EXTENSIONS.iter().any(|ext| name.ends_with(ext))
+}
+
+/// Compresses the file at `path`, removing the original once the compressed file is complete
+///
+/// The compressed file is written under a temporary name first, so a crash halfway
+/// through leaves the original file in place to be compressed again.
+pub(crate) fn compress_file(path: &Path, compression: Compression) -> io::Result<()> {
+    let Some(extension) = compression.extension() else {
+        return Ok(());
+    };
+
+    let target = with_extension_suffix(path, extension);
+    let partial = with_extension_suffix(&target, ".tmp");
+
+    let mut input = File::open(path)?;
+    let output = File::create(&partial)?;
+    match compression {
+        Compression::None => unreachable!(),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(output, flate2::Compression::default());
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?.sync_all()?;
+        }
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let mut encoder = zstd::Encoder::new(output, 0)?;
+            io::copy(&mut input, &mut encoder)?;
+            encoder.finish()?.sync_all()?;
+        }
+    }
+
+    fs::rename(&partial, &target)?;
+    fs::remove_file(path)
+}
+
+/// Compresses files on a background thread, so logging never waits on it
+pub(crate) struct Compressor {
+    sender: Option<Sender<PathBuf>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Compressor {
+    pub(crate) fn spawn(compression: Compression) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<PathBuf>();
+        let handle = thread::Builder::new()
+            .name("tlogger-compression".to_string())
+            .spawn(move || {
+                for path in receiver {
+                    match compress_file(&path, compression) {
+                        // Removed by the retention policy in the meantime
+                        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            eprintln!("Error compressing log file {}: {e}", path.display())
+                        }
+                        Ok(()) => {}
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    /// Queues a file to be compressed
+    pub(crate) fn compress(&self, path: PathBuf) {
+        if let Some(sender) = &self.sender {
+            _ = sender.send(path);
+        }
+    }
+}
+
+impl Drop for Compressor {
+    /// Finishes compressing all queued files
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            _ = handle.join();
+        }
+    }
+}
+
+/// Reads a log file, decompressing it first if it was compressed by the logger
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::logger::read_log_file;
+///
+/// let contents = read_log_file("Logs/2024-11-20-10h-11h.log.gz").unwrap();
+/// for line in contents.lines() {
+///     println!("{line}");
+/// }
+/// ```
+pub fn read_log_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let path = path.as_ref();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let file = File::open(path)?;
+
+    let mut contents = String::new();
+    if name.ends_with(".gz") {
+        GzDecoder::new(file).read_to_string(&mut contents)?;
+    } else if name.ends_with(".zst") {
+        #[cfg(feature = "zstd")]
+        zstd::Decoder::new(file)?.read_to_string(&mut contents)?;
+        #[cfg(not(feature = "zstd"))]
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "reading zstd compressed log files requires the `zstd` feature",
+        ));
+    } else {
+        let mut file = file;
+        file.read_to_string(&mut contents)?;
+    }
+    Ok(contents)
+}
@@ -0,0 +1,274 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use crate::{format::LogFormat, record::LogRecord, timezone::Timezone};
+
+use super::{
+    compression::{self, Compressor},
+    naming::FileNaming,
+    retention::{self, Retention},
+    LogInterval, LogLevel,
+};
+
+/// How long a free disk space measurement is trusted before checking again
+const DISK_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Writes records to the log files of a directory, rotating and pruning them as it goes
+///
+/// Shared between the `Logger` and its background writer thread, if any.
+pub(crate) struct FileWriter {
+    pub(crate) base_path: PathBuf,
+    pub(crate) log_interval: LogInterval,
+    pub(crate) format: LogFormat,
+    pub(crate) naming: FileNaming,
+    timezone: Timezone,
+    pub(crate) max_file_size: Option<u64>,
+    pub(crate) retention: Retention,
+    pub(crate) min_free_disk: Option<u64>,
+    pub(crate) compressor: Option<Compressor>,
+    /// The path of the current file relative to the log directory, without numbering
+    /// or extension, see `FileNaming::stem`
+    timestamp: String,
+    /// The numbered part of the current file, `0` being the file without a number
+    index: u32,
+    /// The current file, kept open until the logger moves on to a new file
+    file: Option<BufWriter<File>>,
+    /// The size of the current file, including anything not yet flushed
+    size: u64,
+    /// When free disk space was last checked
+    disk_checked_at: Option<Instant>,
+    /// Whether free disk space was below `min_free_disk` at the last check
+    low_on_disk: bool,
+}
+
+impl FileWriter {
+    pub(crate) fn new(base_path: PathBuf, log_interval: LogInterval) -> Self {
+        Self {
+            base_path,
+            log_interval,
+            format: LogFormat::default(),
+            naming: FileNaming::default(),
+            timezone: Timezone::default(),
+            max_file_size: None,
+            retention: Retention::default(),
+            min_free_disk: None,
+            compressor: None,
+            timestamp: String::new(),
+            index: 0,
+            file: None,
+            size: 0,
+            disk_checked_at: None,
+            low_on_disk: false,
+        }
+    }
+
+    /// Use a new time zone, moving on to a file named accordingly with the next record
+    pub(crate) fn set_timezone(&mut self, timezone: Timezone) {
+        _ = self.close_file();
+        self.timezone = timezone;
+        self.timestamp.clear();
+    }
+
+    /// Start a new numbered file for the current interval
+    pub(crate) fn rotate_now(&mut self) -> std::io::Result<()> {
+        self.close_file()?;
+        let previous = (self.timestamp.clone(), self.index);
+        self.update_timestamp();
+        self.index += 1;
+        self.rotated(previous)
+    }
+
+    fn file_path(&self, timestamp: &str, index: u32) -> PathBuf {
+        let extension = match &self.naming.extension {
+            Some(extension) => extension.as_str(),
+            None => self.format.extension(),
+        };
+        let file_name = match index {
+            0 => format!("{}.{}", timestamp, extension),
+            n => format!("{}.{}.{}", timestamp, n, extension),
+        };
+        self.base_path.join(file_name)
+    }
+
+    /// Use a new naming, moving on to a file named accordingly with the next record
+    pub(crate) fn set_naming(&mut self, naming: FileNaming) -> std::io::Result<()> {
+        naming.validate()?;
+        self.close_file()?;
+        self.naming = naming;
+        self.timestamp.clear();
+        Ok(())
+    }
+
+    /// Moves on to the files of a new interval once the current one has ended
+    ///
+    /// The highest numbered file of the new interval is continued, so a restarted
+    /// program doesn't append to a file that was already rotated.
+    fn update_timestamp(&mut self) {
+        let timestamp = self
+            .naming
+            .stem(&self.log_interval, self.timezone, self.timezone.now());
+        if self.timestamp == timestamp {
+            return;
+        }
+
+        let mut index = 0;
+        while compression::exists_in_any_form(&self.file_path(&timestamp, index + 1)) {
+            index += 1;
+        }
+
+        self.timestamp = timestamp;
+        self.index = index;
+    }
+
+    /// Compresses the file the logger moved away from, removes old files according
+    /// to the retention policy and points the latest link at the new file
+    fn rotated(&self, (timestamp, index): (String, u32)) -> std::io::Result<()> {
+        let current = self.file_path(&self.timestamp, self.index);
+
+        if timestamp.is_empty() {
+            // Compressions an earlier run didn't finish are started over from the original
+            for path in self.naming.partial_files(&self.base_path)? {
+                _ = fs::remove_file(path);
+            }
+        }
+
+        if let Some(compressor) = &self.compressor {
+            if timestamp.is_empty() {
+                // First file since startup, pick up anything an earlier run left behind
+                for (path, _) in self.naming.log_files(&self.base_path)? {
+                    if path != current && !compression::is_compressed(&path) {
+                        compressor.compress(path);
+                    }
+                }
+            } else {
+                compressor.compress(self.file_path(&timestamp, index));
+            }
+        }
+
+        self.naming.update_latest_link(&self.base_path, &current)?;
+        self.retention
+            .prune(&self.base_path, &current, &self.naming)
+    }
+
+    /// Whether free disk space is below `min_free_disk`, writing a warning to the file
+    /// when it first drops below
+    fn check_disk_space(&mut self) -> std::io::Result<bool> {
+        let Some(min_free_disk) = self.min_free_disk else {
+            return Ok(false);
+        };
+        if self
+            .disk_checked_at
+            .is_some_and(|checked_at| checked_at.elapsed() < DISK_CHECK_INTERVAL)
+        {
+            return Ok(self.low_on_disk);
+        }
+
+        let low_on_disk = retention::available_space(&self.base_path)
+            .is_some_and(|available| available < min_free_disk);
+        let newly_low = low_on_disk && !self.low_on_disk;
+        self.disk_checked_at = Some(Instant::now());
+        self.low_on_disk = low_on_disk;
+
+        if newly_low {
+            let warning = LogRecord::new(
+                LogLevel::Warn,
+                "Logger",
+                format!(
+                    "Less than {} bytes of disk space left, debug, info and success logs are no longer written to disk",
+                    min_free_disk
+                ),
+            );
+            self.write_line(&self.format.format_with_timezone(&warning, self.timezone))?;
+        }
+
+        Ok(low_on_disk)
+    }
+
+    /// Flushes and closes the current file, the next write opens the file anew
+    pub(crate) fn close_file(&mut self) -> std::io::Result<()> {
+        match self.file.take() {
+            Some(mut file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn open_file(&mut self) -> std::io::Result<()> {
+        let file_path = self.file_path(&self.timestamp, self.index);
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)?;
+        self.size = file.metadata()?.len();
+        self.file = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    /// The file a line of `len` bytes is written to, moving on to a new file when
+    /// the interval has ended or the size limit would be exceeded
+    fn get_log_file(&mut self, len: u64) -> std::io::Result<&mut BufWriter<File>> {
+        let previous = (self.timestamp.clone(), self.index);
+        self.update_timestamp();
+        if self.timestamp != previous.0 {
+            self.close_file()?;
+        }
+        if self.file.is_none() {
+            self.open_file()?;
+        }
+
+        if let Some(max_file_size) = self.max_file_size {
+            if self.size > 0 && self.size + len > max_file_size {
+                self.close_file()?;
+                self.index += 1;
+                self.open_file()?;
+            }
+        }
+
+        if (&self.timestamp, self.index) != (&previous.0, previous.1) {
+            self.rotated(previous)?;
+        }
+
+        Ok(self.file.as_mut().expect("log file was opened above"))
+    }
+
+    /// Writes a whole line to the current file, so lines never interleave
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64;
+        self.get_log_file(len)?.write_all(line.as_bytes())?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Flushes buffered lines to the current file
+    pub(crate) fn flush(&mut self) -> std::io::Result<()> {
+        match &mut self.file {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes a record to the current log file, without flushing it
+    ///
+    /// Debug, info and success records are skipped while the disk is low on space.
+    pub(crate) fn write(&mut self, record: &LogRecord) -> std::io::Result<()> {
+        let line = self.format.format_with_timezone(record, self.timezone);
+
+        let low_on_disk = self.check_disk_space()?;
+        if low_on_disk
+            && matches!(
+                record.level,
+                LogLevel::Trace | LogLevel::Debug | LogLevel::Info | LogLevel::Success
+            )
+        {
+            return Ok(());
+        }
+
+        self.write_line(&line)
+    }
+}
@@ -0,0 +1,515 @@
+use std::{
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, TryLockError},
+    time::Duration,
+};
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+use crate::{
+    filter::file_directives, format::LogFormat, get_colors, get_symbols, record::LogRecord,
+    sinks::Sink, timezone::Timezone,
+};
+
+pub use background::OverflowPolicy;
+pub use compression::{read_log_file, Compression};
+pub use naming::FileNaming;
+pub use retention::Retention;
+
+use background::BackgroundWriter;
+use compression::Compressor;
+use file::FileWriter;
+
+mod background;
+mod compression;
+mod file;
+mod naming;
+mod retention;
+
+/// How often the logger moves on to a new file
+///
+/// Intervals are computed in the time zone of the logger, see `Logger::set_timezone`.
+/// Intervals shorter than a day are aligned to midnight. When an interval doesn't
+/// divide the day evenly, the last file of the day is cut short at midnight, so
+/// `NineHour` writes `00h-09h`, `09h-18h` and `18h-00h` files and every file
+/// covers a single date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogInterval {
+    /// `2024-11-20-10h15-10h16`
+    OneMinute,
+    /// `2024-11-20-10h15-10h20`
+    FiveMinute,
+    /// `2024-11-20-10h15-10h30`
+    FifteenMinute,
+    /// `2024-11-20-10h30-11h00`
+    ThirtyMinute,
+    /// `2024-11-20-10h-11h`
+    OneHour,
+    /// `2024-11-20-09h-12h`
+    ThreeHour,
+    /// `2024-11-20-06h-12h`
+    SixHour,
+    /// `2024-11-20-09h-18h`
+    NineHour,
+    /// `2024-11-20-12h-00h`
+    TwelveHour,
+    /// `2024-11-20-00h-24h`
+    OneDay,
+    /// The ISO week, `2024-W47`
+    Weekly,
+    /// The calendar month, `2024-11`
+    Monthly,
+    /// Any interval, rounded down to whole seconds
+    ///
+    /// Intervals shorter than a day follow the same rule as the fixed ones, with
+    /// seconds in the file name if needed (`2024-11-20-10h15m30s-10h16m00s`). Longer
+    /// intervals are rounded down to whole days, counted from 1970-01-01, and named
+    /// after their first day and length: `2024-11-19-3d`.
+    Every(Duration),
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+impl LogInterval {
+    /// The length of the interval in seconds, if it is a fixed length
+    fn seconds(&self) -> Option<u64> {
+        match self {
+            LogInterval::OneMinute => Some(60),
+            LogInterval::FiveMinute => Some(5 * 60),
+            LogInterval::FifteenMinute => Some(15 * 60),
+            LogInterval::ThirtyMinute => Some(30 * 60),
+            LogInterval::OneHour => Some(60 * 60),
+            LogInterval::ThreeHour => Some(3 * 60 * 60),
+            LogInterval::SixHour => Some(6 * 60 * 60),
+            LogInterval::NineHour => Some(9 * 60 * 60),
+            LogInterval::TwelveHour => Some(12 * 60 * 60),
+            LogInterval::OneDay => Some(SECONDS_PER_DAY),
+            LogInterval::Every(duration) => Some(duration.as_secs().max(1)),
+            LogInterval::Weekly | LogInterval::Monthly => None,
+        }
+    }
+
+    /// The part of the file name identifying the interval `now` falls in
+    pub(crate) fn get_file_timestamp(&self, now: DateTime<FixedOffset>) -> String {
+        match (self, self.seconds()) {
+            (LogInterval::OneDay, _) => format!("{}-00h-24h", now.format("%Y-%m-%d")),
+            (LogInterval::Weekly, _) => now.format("%G-W%V").to_string(),
+            (LogInterval::Monthly, _) => now.format("%Y-%m").to_string(),
+            (_, Some(seconds)) if seconds >= SECONDS_PER_DAY => {
+                let start = self.period_start(now);
+                format!(
+                    "{}-{}d",
+                    start.format("%Y-%m-%d"),
+                    seconds / SECONDS_PER_DAY
+                )
+            }
+            (_, Some(seconds)) => {
+                let elapsed = now.num_seconds_from_midnight() as u64;
+                let start = elapsed - elapsed % seconds;
+                let end = (start + seconds).min(SECONDS_PER_DAY);
+                format!(
+                    "{}-{}-{}",
+                    now.format("%Y-%m-%d"),
+                    time_of_day_label(start, seconds),
+                    time_of_day_label(end % SECONDS_PER_DAY, seconds)
+                )
+            }
+            (_, None) => unreachable!("only calendar intervals have no fixed length"),
+        }
+    }
+
+    /// The local date and time the interval `now` falls in started
+    pub(crate) fn period_start(&self, now: DateTime<FixedOffset>) -> NaiveDateTime {
+        let date = now.date_naive();
+        match (self, self.seconds()) {
+            (LogInterval::Weekly, _) => {
+                let monday = date - chrono::Days::new(now.weekday().num_days_from_monday() as u64);
+                monday.and_time(NaiveTime::MIN)
+            }
+            (LogInterval::Monthly, _) => date.with_day(1).unwrap().and_time(NaiveTime::MIN),
+            (_, Some(seconds)) if seconds >= SECONDS_PER_DAY => {
+                let days = seconds / SECONDS_PER_DAY;
+                let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+                let day = (date - epoch).num_days() as u64;
+                (epoch + chrono::Days::new(day - day % days)).and_time(NaiveTime::MIN)
+            }
+            (_, Some(seconds)) => {
+                let elapsed = now.num_seconds_from_midnight() as u64;
+                let start = elapsed - elapsed % seconds;
+                date.and_time(NaiveTime::MIN) + chrono::Duration::seconds(start as i64)
+            }
+            (_, None) => unreachable!("only calendar intervals have no fixed length"),
+        }
+    }
+}
+
+/// Formats seconds since midnight as precisely as an interval of `seconds` needs,
+/// `10h`, `10h15` or `10h15m30s`
+fn time_of_day_label(time: u64, seconds: u64) -> String {
+    let (hours, minutes, secs) = (time / 3600, time / 60 % 60, time % 60);
+    if seconds.is_multiple_of(3600) {
+        format!("{:02}h", hours)
+    } else if seconds.is_multiple_of(60) {
+        format!("{:02}h{:02}", hours, minutes)
+    } else {
+        format!("{:02}h{:02}m{:02}s", hours, minutes, secs)
+    }
+}
+
+/// The severity of a record, ordered from `Trace` to `Error`
+///
+/// `Success` sits between `Info` and `Warn`, so a threshold of `Info` includes
+/// success records and a threshold of `Warn` leaves them out.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Success,
+    Warn,
+    Error,
+}
+
+/// A level name that couldn't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError(String);
+
+impl std::fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown log level `{}`", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(self.as_str())
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = ParseLevelError;
+
+    /// Parses a level name case-insensitively, accepting `warning` for `Warn`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "success" => Ok(LogLevel::Success),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" => Ok(LogLevel::Error),
+            _ => Err(ParseLevelError(s.to_string())),
+        }
+    }
+}
+
+impl LogLevel {
+    /// The configured title/box color for this level
+    pub fn color(&self) -> &'static str {
+        let colors = get_colors();
+        match self {
+            LogLevel::Trace => colors.trace,
+            LogLevel::Debug => colors.debug,
+            LogLevel::Info => colors.info,
+            LogLevel::Warn => colors.warn,
+            LogLevel::Error => colors.error,
+            LogLevel::Success => colors.success,
+        }
+    }
+
+    /// The configured message text color for this level
+    pub fn text_color(&self) -> &'static str {
+        let colors = get_colors();
+        match self {
+            LogLevel::Trace => colors.trace_text,
+            LogLevel::Debug => colors.debug_text,
+            LogLevel::Info => colors.info_text,
+            LogLevel::Warn => colors.warn_text,
+            LogLevel::Error => colors.error_text,
+            LogLevel::Success => colors.success_text,
+        }
+    }
+
+    /// The configured symbol for this level
+    pub fn symbol(&self) -> &'static str {
+        let symbols = get_symbols();
+        match self {
+            LogLevel::Trace => symbols.trace,
+            LogLevel::Debug => symbols.debug,
+            LogLevel::Info => symbols.info,
+            LogLevel::Warn => symbols.warn,
+            LogLevel::Error => symbols.error,
+            LogLevel::Success => symbols.success,
+        }
+    }
+
+    /// The lowercase name of this level, as used in log files
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+            LogLevel::Success => "success",
+        }
+    }
+
+    /// The syslog severity of this level, also used as the journald priority
+    ///
+    /// Success maps to notice, trace and debug both map to debug.
+    pub fn syslog_severity(&self) -> u8 {
+        match self {
+            LogLevel::Trace | LogLevel::Debug => 7,
+            LogLevel::Info => 6,
+            LogLevel::Success => 5,
+            LogLevel::Warn => 4,
+            LogLevel::Error => 3,
+        }
+    }
+}
+
+pub struct Logger {
+    log_levels: Vec<LogLevel>,
+    min_level: LogLevel,
+    writer: Arc<Mutex<FileWriter>>,
+    background: Option<BackgroundWriter>,
+}
+
+/// Locks a mutex, carrying on with the data of a thread that panicked while holding it
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Logger {
+    pub fn new<P: Into<PathBuf>>(base_path: P, log_interval: LogInterval) -> std::io::Result<Self> {
+        let base_path = base_path.into();
+        fs::create_dir_all(&base_path)?;
+        Ok(Logger {
+            log_levels: vec![
+                LogLevel::Trace,
+                LogLevel::Debug,
+                LogLevel::Info,
+                LogLevel::Warn,
+                LogLevel::Error,
+                LogLevel::Success,
+            ],
+            min_level: LogLevel::Trace,
+            writer: Arc::new(Mutex::new(FileWriter::new(base_path, log_interval))),
+            background: None,
+        })
+    }
+
+    /// Clear all log levels
+    pub fn clear_log_levels(&mut self) {
+        self.log_levels.clear();
+    }
+
+    /// Add the types of logs to be saved to log files.
+    pub fn add_log_level(&mut self, log_level: LogLevel) {
+        self.log_levels.push(log_level);
+    }
+
+    /// Only save records of this level or a more severe one
+    ///
+    /// Applies on top of the saved log levels, all levels are saved by default.
+    pub fn set_min_level(&mut self, level: LogLevel) {
+        self.min_level = level;
+    }
+
+    /// Set the format log files are written in
+    ///
+    /// Text files use the `.log` extension and JSON Lines files `.jsonl`.
+    pub fn set_format(&mut self, format: LogFormat) {
+        let mut writer = self.lock_writer();
+        writer.format = format;
+        // The extension changed, so the open file is no longer the current one
+        _ = writer.close_file();
+    }
+
+    /// Set how log files are named and laid out in the log directory
+    ///
+    /// Fails if one of the strftime patterns is invalid. The next record is written
+    /// to a file named accordingly.
+    pub fn set_file_naming(&mut self, naming: FileNaming) -> std::io::Result<()> {
+        self.flush();
+        self.lock_writer().set_naming(naming)
+    }
+
+    /// Set the time zone used for interval boundaries, file names and the
+    /// timestamps in log files
+    ///
+    /// With `Timezone::Local`, a file name gets the UTC offset appended when the
+    /// offset changed since the start of its interval, `2024-10-27-02h-03h+0100.log`.
+    /// This keeps the repeated hour after a daylight saving time change in a file
+    /// of its own.
+    pub fn set_timezone(&mut self, timezone: Timezone) {
+        self.flush();
+        self.lock_writer().set_timezone(timezone);
+    }
+
+    /// Also rotate when the current file would grow past `bytes`
+    ///
+    /// Files of the same interval are numbered, `2024-11-20-10h-11h.log` is followed
+    /// by `2024-11-20-10h-11h.1.log`, `2024-11-20-10h-11h.2.log` and so on.
+    /// A single record larger than the limit is still written to a file of its own.
+    pub fn set_max_file_size(&mut self, bytes: u64) {
+        self.lock_writer().max_file_size = Some(bytes);
+    }
+
+    /// Set limits on the age, number and total size of the files kept in the log directory
+    ///
+    /// Files outside of these limits are removed whenever the logger moves on to a new file.
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.lock_writer().retention = retention;
+    }
+
+    /// Stop writing debug, info and success logs to disk once the free space of the
+    /// log directory's file system drops below `bytes`
+    ///
+    /// A single warning is written to the log file when this happens, regardless of
+    /// the saved log levels, and is not printed to the console. Warnings and errors are still written. Free space can only be
+    /// measured on Unix systems.
+    pub fn set_min_free_disk(&mut self, bytes: u64) {
+        self.lock_writer().min_free_disk = Some(bytes);
+    }
+
+    /// Compress files once the logger has moved on to a new file
+    ///
+    /// Compression runs on a background thread. Uncompressed files left behind by
+    /// an earlier run, for example after a crash, are compressed once the logger
+    /// writes its first record.
+    pub fn set_compression(&mut self, compression: Compression) -> std::io::Result<()> {
+        let compressor = match compression {
+            Compression::None => None,
+            compression => Some(Compressor::spawn(compression)?),
+        };
+        self.lock_writer().compressor = compressor;
+        Ok(())
+    }
+
+    /// Write records to disk on a dedicated thread instead of the logging thread
+    ///
+    /// Records are queued until the writer thread gets to them, with at most
+    /// `capacity` records waiting. `overflow` decides what happens to records
+    /// logged while the queue is full. When records are dropped, a warning with
+    /// the number of dropped records is written once the writer catches up.
+    ///
+    /// Queued records are written when the logger is dropped, or when the program
+    /// exits normally for a logger added with `add_sink`. Call [`Logger::flush`] to
+    /// wait for them at any other point.
+    pub fn set_async(&mut self, capacity: usize, overflow: OverflowPolicy) -> std::io::Result<()> {
+        // Write whatever the previous writer thread still has queued first
+        self.background.take();
+        self.background = Some(BackgroundWriter::spawn(
+            Arc::clone(&self.writer),
+            capacity,
+            overflow,
+        )?);
+        Ok(())
+    }
+
+    /// Wait until all queued records have been written
+    ///
+    /// Returns right away if the logger isn't writing on a background thread.
+    pub fn flush(&self) {
+        if let Some(background) = &self.background {
+            background.flush();
+        }
+    }
+
+    /// Start a new numbered file for the current interval, even if the size limit
+    /// has not been reached
+    ///
+    /// Records queued before the call are written to the previous file.
+    pub fn rotate_now(&self) -> std::io::Result<()> {
+        self.flush();
+        self.lock_writer().rotate_now()
+    }
+
+    fn level_enabled(&self, level: &LogLevel) -> bool {
+        *level >= self.min_level && self.log_levels.contains(level)
+    }
+
+    fn lock_writer(&self) -> MutexGuard<'_, FileWriter> {
+        lock(&self.writer)
+    }
+
+    /// Write a record to the current log file, if its level is one of the saved log
+    /// levels, at least the minimum level and the `TLOGGER_FILE` directives let it through
+    ///
+    /// When writing on a background thread the record is only queued, and errors
+    /// are printed by the writer thread instead of returned.
+    pub fn log(&self, record: &LogRecord) -> std::io::Result<()> {
+        if !self.level_enabled(&record.level) || !file_directives().enabled(record) {
+            return Ok(());
+        }
+        self.write(record)
+    }
+
+    /// Like [`Logger::log`], for the global `LOGGER`: a `TLOGGER_FILE` directive
+    /// matching the record replaces the saved log levels and the minimum level
+    pub(crate) fn log_global(&self, record: &LogRecord) -> std::io::Result<()> {
+        if !file_directives().enabled_or(record, || self.level_enabled(&record.level)) {
+            return Ok(());
+        }
+        self.write(record)
+    }
+
+    fn write(&self, record: &LogRecord) -> std::io::Result<()> {
+        match &self.background {
+            Some(background) => {
+                background.send(record.clone());
+                Ok(())
+            }
+            // Hold the lock while writing, so the size check stays accurate and
+            // lines written from different threads never interleave
+            None => {
+                let mut writer = self.lock_writer();
+                writer.write(record)?;
+                writer.flush()
+            }
+        }
+    }
+
+    /// Like [`Logger::log_global`], but skips the record instead of waiting for the
+    /// file or the queue to be unlocked, for the panic hook
+    pub(crate) fn try_log(&self, record: &LogRecord) -> std::io::Result<()> {
+        if !file_directives().enabled_or(record, || self.level_enabled(&record.level)) {
+            return Ok(());
+        }
+        match &self.background {
+            Some(background) => {
+                background.try_send(record.clone());
+                Ok(())
+            }
+            None => {
+                let mut writer = match self.writer.try_lock() {
+                    Ok(writer) => writer,
+                    Err(TryLockError::Poisoned(e)) => e.into_inner(),
+                    Err(TryLockError::WouldBlock) => return Ok(()),
+                };
+                writer.write(record)?;
+                writer.flush()
+            }
+        }
+    }
+}
+
+impl Sink for Logger {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.level_enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> std::io::Result<()> {
+        Logger::log(self, record)
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        Logger::flush(self);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, FixedOffset,
+};
+use regex::Regex;
+
+use crate::timezone::Timezone;
+
+use super::{compression::COMPRESSED_EXTENSIONS, LogInterval};
+
+/// File extensions of the files the logger writes with the default naming
+const LOG_EXTENSIONS: [&str; 2] = ["log", "jsonl"];
+
+/// The file name of any interval, as `LogInterval::get_file_timestamp` writes it:
+/// `2024-W47`, `2024-11`, `2024-11-19-3d` or `2024-11-20-10h15m30s-10h16m00s`
+const INTERVAL_STEM: &str = concat!(
+    r"\d{4}-(?:W\d{2}|\d{2}(?:-\d{2}-(?:\d+d|",
+    r"\d{2}h(?:\d{2}(?:m\d{2}s)?)?-\d{2}h(?:\d{2}(?:m\d{2}s)?)?",
+    r"))?)"
+);
+
+/// How log files are named and laid out in the log directory
+///
+/// By default files are named after their interval, `2024-11-20-10h-11h.log`,
+/// directly in the log directory.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_file_naming(FileNaming {
+///     prefix: Some("my-app-".to_string()),
+///     directories: Some("%Y/%m/%d".to_string()),
+///     latest_link: Some("latest.log".to_string()),
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// // Logs/2024/11/20/my-app-2024-11-20-10h-11h.log, with Logs/latest.log pointing at it
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileNaming {
+    /// Put in front of every file name as is
+    pub prefix: Option<String>,
+    /// A strftime pattern formatted with the start of the interval, used instead of
+    /// the interval name
+    ///
+    /// The pattern should tell the intervals apart, `%Y-%m-%d_%H` for hourly files.
+    /// A `/` in the pattern puts the files in nested directories, like `directories`.
+    pub pattern: Option<String>,
+    /// The extension used instead of the one of the log format, without the dot
+    pub extension: Option<String>,
+    /// A strftime pattern formatted with the start of the interval, for nested
+    /// directories below the log directory such as `%Y/%m/%d`
+    pub directories: Option<String>,
+    /// The name of a symlink in the log directory that always points at the current
+    /// file, such as `latest.log`
+    ///
+    /// Only created on Unix systems.
+    pub latest_link: Option<String>,
+}
+
+impl FileNaming {
+    /// Checks that the strftime patterns are valid
+    pub(crate) fn validate(&self) -> io::Result<()> {
+        for pattern in [&self.pattern, &self.directories].into_iter().flatten() {
+            if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("invalid strftime pattern `{}`", pattern),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The path of the files of the interval `now` falls in, relative to the log
+    /// directory and without numbering or extension
+    ///
+    /// The UTC offset is appended when it changed since the start of the interval.
+    pub(crate) fn stem(
+        &self,
+        interval: &LogInterval,
+        timezone: Timezone,
+        now: DateTime<FixedOffset>,
+    ) -> String {
+        let start = timezone.resolve(interval.period_start(now)).unwrap_or(now);
+
+        let mut stem = String::new();
+        if let Some(directories) = &self.directories {
+            stem.push_str(&start.format(directories).to_string());
+            stem.push('/');
+        }
+        let name = match &self.pattern {
+            Some(pattern) => start.format(pattern).to_string(),
+            None => interval.get_file_timestamp(now),
+        };
+        // Directories in the pattern come before the prefix, which belongs to the file name
+        let (name_directories, file_name) = name.rsplit_once('/').unwrap_or(("", &name));
+        if !name_directories.is_empty() {
+            stem.push_str(name_directories);
+            stem.push('/');
+        }
+        if let Some(prefix) = &self.prefix {
+            stem.push_str(prefix);
+        }
+        stem.push_str(file_name);
+        if start.offset() != now.offset() {
+            stem.push_str(&now.format("%z").to_string());
+        }
+        stem
+    }
+
+    /// Matches the names of the files the logger writes with this naming: the prefix,
+    /// the interval or pattern, an optional UTC offset, rotation index and the
+    /// extension, followed by `compressed`, a regex for the compressed extensions
+    fn file_name_regex(&self, compressed: &str) -> Regex {
+        let stem = match &self.pattern {
+            Some(pattern) => {
+                // Directories in the pattern are not part of the file name
+                let file_name = pattern.rsplit('/').next().unwrap_or_default();
+                StrftimeItems::new(file_name).map(pattern_regex).collect()
+            }
+            None => INTERVAL_STEM.to_string(),
+        };
+        let extensions = match &self.extension {
+            Some(extension) => regex::escape(extension),
+            None => LOG_EXTENSIONS.join("|"),
+        };
+
+        Regex::new(&format!(
+            r"^{}{}(?:[+-]\d{{4}})?(?:\.\d+)?\.(?:{}){}$",
+            regex::escape(self.prefix.as_deref().unwrap_or_default()),
+            stem,
+            extensions,
+            compressed,
+        ))
+        .expect("file name patterns are escaped")
+    }
+
+    /// All log files below `dir`, including those in nested directories
+    ///
+    /// Only regular files the logger wrote are included, so the `latest_link` symlink
+    /// and files of other programs never are.
+    pub(crate) fn log_files(&self, dir: &Path) -> io::Result<Vec<(PathBuf, fs::Metadata)>> {
+        let regex = self.file_name_regex(&format!("(?:{})?", compressed_extensions()));
+        let mut files = Vec::new();
+        self.collect_log_files(dir, &regex, &mut files)?;
+        Ok(files)
+    }
+
+    /// Partial compressed files below `dir`, left behind by a compression that was
+    /// interrupted
+    pub(crate) fn partial_files(&self, dir: &Path) -> io::Result<Vec<PathBuf>> {
+        let regex = self.file_name_regex(&format!(r"(?:{})\.tmp", compressed_extensions()));
+        let mut files = Vec::new();
+        self.collect_log_files(dir, &regex, &mut files)?;
+        Ok(files.into_iter().map(|(path, _)| path).collect())
+    }
+
+    fn collect_log_files(
+        &self,
+        dir: &Path,
+        file_name: &Regex,
+        files: &mut Vec<(PathBuf, fs::Metadata)>,
+    ) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                // Removed by the compressor in the meantime
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            if metadata.is_dir() && self.has_directories() {
+                self.collect_log_files(&path, file_name, files)?;
+            } else if metadata.is_file() && file_name.is_match(&entry.file_name().to_string_lossy())
+            {
+                files.push((path, metadata));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether files are put in nested directories, by `directories` or by a `/` in
+    /// `pattern`
+    fn has_directories(&self) -> bool {
+        self.directories.is_some()
+            || self
+                .pattern
+                .as_ref()
+                .is_some_and(|pattern| pattern.contains('/'))
+    }
+
+    /// Points the `latest_link` symlink at `current`
+    ///
+    /// The link is replaced in a single rename, so readers never find it missing.
+    #[cfg(unix)]
+    pub(crate) fn update_latest_link(&self, dir: &Path, current: &Path) -> io::Result<()> {
+        let Some(latest_link) = &self.latest_link else {
+            return Ok(());
+        };
+        let link = dir.join(latest_link);
+        let partial = dir.join(format!("{}.tmp", latest_link));
+        let target = current.strip_prefix(dir).unwrap_or(current);
+
+        _ = fs::remove_file(&partial);
+        std::os::unix::fs::symlink(target, &partial)?;
+        fs::rename(&partial, &link)
+    }
+
+    /// Points the `latest_link` symlink at `current`
+    #[cfg(not(unix))]
+    pub(crate) fn update_latest_link(&self, _dir: &Path, _current: &Path) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A regex matching any of the compressed extensions
+fn compressed_extensions() -> String {
+    COMPRESSED_EXTENSIONS
+        .iter()
+        .map(|ext| regex::escape(ext))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// A regex for the text a strftime item formats to
+fn pattern_regex(item: Item) -> String {
+    match item {
+        Item::Literal(literal) => regex::escape(literal),
+        Item::OwnedLiteral(literal) => regex::escape(&literal),
+        Item::Space(_) | Item::OwnedSpace(_) => r"\s*".to_string(),
+        Item::Numeric(..) => r"[+-]?\d+".to_string(),
+        // Names, offsets and fractions of seconds
+        Item::Fixed(_) | Item::Error => r"[^/]+?".to_string(),
+    }
+}
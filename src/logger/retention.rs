@@ -0,0 +1,118 @@
+use std::{
+    fs, io,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use super::naming::FileNaming;
+
+/// Limits on the log files kept in the log directory
+///
+/// Old files are pruned whenever the logger moves on to a new file, oldest first.
+/// The file currently being written to is never removed.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_retention(Retention {
+///     max_age_days: Some(14),
+///     max_total_bytes: Some(1024 * 1024 * 1024), // 1 GiB
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Retention {
+    /// Remove files last written to more than this many days ago
+    pub max_age_days: Option<u64>,
+    /// Keep at most this many files, including the current one
+    pub max_files: Option<usize>,
+    /// Keep the combined size of all files, including the current one, below this many bytes
+    pub max_total_bytes: Option<u64>,
+}
+
+impl Retention {
+    fn is_unlimited(&self) -> bool {
+        self.max_age_days.is_none() && self.max_files.is_none() && self.max_total_bytes.is_none()
+    }
+
+    /// Removes the log files below `dir` that fall outside of this policy
+    ///
+    /// Nested directories left empty are removed as well.
+    pub(crate) fn prune(&self, dir: &Path, current: &Path, naming: &FileNaming) -> io::Result<()> {
+        if self.is_unlimited() {
+            return Ok(());
+        }
+
+        let mut files = Vec::new();
+        for (path, metadata) in naming.log_files(dir)? {
+            if path != current {
+                files.push((metadata.modified()?, metadata.len(), path));
+            }
+        }
+
+        // Newest first, so the oldest files are the ones to go
+        files.sort_by_key(|(modified, _, _)| std::cmp::Reverse(*modified));
+
+        let now = SystemTime::now();
+        let mut kept_files = 1;
+        let mut kept_bytes = fs::metadata(current).map(|m| m.len()).unwrap_or(0);
+
+        for (modified, len, path) in files {
+            let too_old = self.max_age_days.is_some_and(|days| {
+                now.duration_since(modified).unwrap_or_default()
+                    > Duration::from_secs(days * 24 * 60 * 60)
+            });
+            let too_many = self.max_files.is_some_and(|max| kept_files >= max);
+            let too_large = self
+                .max_total_bytes
+                .is_some_and(|max| kept_bytes + len > max);
+
+            if too_old || too_many || too_large {
+                match fs::remove_file(&path) {
+                    // Compressed under another name by the compressor in the meantime
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                    result => result?,
+                }
+                remove_empty_parents(dir, &path);
+            } else {
+                kept_files += 1;
+                kept_bytes += len;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Removes the directories between `dir` and `path` that are left empty
+fn remove_empty_parents(dir: &Path, path: &Path) {
+    for parent in path.ancestors().skip(1) {
+        if parent == dir || !parent.starts_with(dir) || fs::remove_dir(parent).is_err() {
+            break;
+        }
+    }
+}
+
+/// Space available to unprivileged users on the file system containing `path`
+#[cfg(unix)]
+pub(crate) fn available_space(path: &Path) -> Option<u64> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    // The field types differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Space available to unprivileged users on the file system containing `path`
+#[cfg(not(unix))]
+pub(crate) fn available_space(_path: &Path) -> Option<u64> {
+    None
+}
@@ -1,4 +1,8 @@
-use crate::{Borders, Colors, Symbols, BORDERS, COLORS, SYMBOLS};
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+
+use crate::{get_colors, style_text, write, Borders, Colors, Symbols, BORDERS, COLORS, SYMBOLS};
 
 /// Converts RGB values to an ANSI escape code
 ///
@@ -62,10 +66,18 @@ macro_rules! ansi_rgb_bg {
     };
 }
 
+/// Matches the ANSI escape codes used for colors and styles
+static ANSI_CODES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
 /// Strips ANSI escape codes from a string
 pub fn strip_ansi_codes(s: &str) -> String {
-    let re = regex::Regex::new(r"\x1b\[[0-9;]*m").unwrap();
-    re.replace_all(s, "").to_string()
+    ANSI_CODES.replace_all(s, "").to_string()
+}
+
+/// Removes `style_text!` markup (`**bold**`, `_underline_`, ...) from a string,
+/// keeping only the text content
+pub fn strip_style_markup(s: &str) -> String {
+    strip_ansi_codes(&style_text!(s, ""))
 }
 
 /// Set your own colors for formatting
@@ -86,7 +98,7 @@ pub fn strip_ansi_codes(s: &str) -> String {
 /// ```
 #[inline]
 pub fn customize_colors(colors: Colors) {
-    _ = COLORS.set(colors);
+    *write(&COLORS) = Arc::new(colors);
 }
 
 /// Set your own symbols for formatting
@@ -105,7 +117,7 @@ pub fn customize_colors(colors: Colors) {
 /// ```
 #[inline]
 pub fn customize_symbols(symbols: Symbols) {
-    _ = SYMBOLS.set(symbols);
+    *write(&SYMBOLS) = Arc::new(symbols);
 }
 
 /// Set your own border characters for formatting
@@ -126,5 +138,5 @@ pub fn customize_symbols(symbols: Symbols) {
 /// ```
 #[inline]
 pub fn customize_borders(borders: Borders) {
-    _ = BORDERS.set(borders);
+    *write(&BORDERS) = Arc::new(borders);
 }
@@ -40,7 +40,7 @@ macro_rules! style_text {
         let do_style = $crate::get_text_styling();
         let text = $text.to_string();
 
-        if *do_style {
+        if do_style {
             let mut result = text.clone();
 
             // Bold (process first as it uses double markers)
@@ -119,26 +119,117 @@ macro_rules! style_text {
 /// Creates a log message with the given title, symbol, and message
 #[macro_export]
 macro_rules! make_log {
-    ($color:expr, $symbol:expr, $title:expr, $textcolor:expr, $($arg:tt)*) => {{
-        let message = format!($($arg)*);
-        let processed_message = style_text!(message, $textcolor);
-
-        format!(
-            "{}{} {} {}{}{}{} {} {}{}{}{}{} {}",
+    ($color:expr, $symbol:expr, $title:expr, $textcolor:expr, $($arg:tt)*) => {
+        $crate::create_log_line(
             $color,
             $symbol,
-            $crate::get_colors().reset,
-            $crate::get_colors().dim,
-            $crate::get_timestamp(),
-            $crate::get_colors().reset,
-            $crate::get_colors().dim,
-            $crate::get_symbols().separator,
-            $crate::get_colors().reset,
-            $crate::get_colors().bold,
-            $color,
-            $title,
-            $crate::get_colors().reset,
-            processed_message
+            &::std::string::ToString::to_string(&$title),
+            $textcolor,
+            &$crate::get_timestamp(),
+            &::std::format!($($arg)*),
+        )
+    };
+}
+
+/// Builds a `LogRecord` from the arguments of a logging macro and logs it
+///
+/// Logs to the given `TLogger` if the arguments start with `logger =>`, otherwise
+/// to the global sinks and `LOGGER`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log {
+    ($level:expr, $boxed:expr, $logger:expr => $title:expr, $($arg:tt)*) => {{
+        let record = $crate::__record!($level, $boxed, $title, $($arg)*);
+        $crate::instance::TLogger::log(&$logger, &record);
+    }};
+    ($level:expr, $boxed:expr, $title:expr, $($arg:tt)*) => {{
+        let record = $crate::__record!($level, $boxed, $title, $($arg)*);
+        $crate::log_record(&record);
+    }};
+}
+
+/// Builds a `LogRecord` from the arguments of a logging macro
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __record {
+    ($level:expr, $boxed:expr, $title:expr, $($arg:tt)*) => {{
+        let (message, fields) = $crate::__log_parts!($($arg)*);
+        $crate::record::LogRecord::new(
+            $level,
+            ::std::string::ToString::to_string(&$title),
+            message,
         )
+        .with_fields(fields)
+        .with_callsite($crate::record::Callsite::new(
+            ::std::module_path!(),
+            ::std::file!(),
+            ::std::line!(),
+        ))
+        .boxed($boxed)
+    }};
+}
+
+/// Splits the arguments of a logging macro into the formatted message and its fields
+///
+/// Accepts a format string and its arguments, optionally followed by `;` and
+/// `key = value` fields. Named format arguments (`"{user}", user = id`) and
+/// variables captured inline (`"{user}"`) are promoted to fields as well. Field
+/// values use their `Display` implementation, or `Debug` if there is none.
+///
+/// Evaluates to a `(String, Fields)` tuple.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __log_parts {
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*]) => {
+        $crate::__implicit_captures!($crate::__log_parts; $fmt [$($pos,)*] [$($name = $value,)*] [])
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] ,) => {
+        $crate::__implicit_captures!($crate::__log_parts; $fmt [$($pos,)*] [$($name = $value,)*] [])
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] ; $($key:ident = $val:expr),* $(,)?) => {
+        $crate::__implicit_captures!($crate::__log_parts; $fmt [$($pos,)*] [$($name = $value,)*] [$($key = $val,)*])
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:ident = $next_value:expr , $($rest:tt)*) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)*] [$($name = $value,)* $next = $next_value,] , $($rest)*)
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:ident = $next_value:expr ; $($rest:tt)*) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)*] [$($name = $value,)* $next = $next_value,] ; $($rest)*)
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:ident = $next_value:expr) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)*] [$($name = $value,)* $next = $next_value,])
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:expr , $($rest:tt)*) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)* $next,] [$($name = $value,)*] , $($rest)*)
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:expr ; $($rest:tt)*) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)* $next,] [$($name = $value,)*] ; $($rest)*)
+    };
+    (@args $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] , $next:expr) => {
+        $crate::__log_parts!(@args $fmt [$($pos,)* $next,] [$($name = $value,)*])
+    };
+    (@finish $fmt:literal [$($pos:expr,)*] [$($name:ident = $value:expr,)*] [$($key:ident = $val:expr,)*]) => {{
+        #[allow(unused_imports)]
+        use $crate::fields::{DebugFieldValue as _, DisplayFieldValue as _};
+
+        #[allow(unused_mut)]
+        let mut fields: $crate::fields::Fields = ::std::vec![$(
+            (::std::stringify!($key), (&$crate::fields::FieldValue(&$val)).to_field_value()),
+        )*];
+
+        // Named arguments are evaluated once and shared by the message and the fields
+        match ($(&$value,)*) {
+            ($($name,)*) => {
+                let message = ::std::format!($fmt, $($pos,)* $($name = $name,)*);
+                #[allow(unused_mut)]
+                let mut promoted: $crate::fields::Fields = ::std::vec![$(
+                    (::std::stringify!($name), (&$crate::fields::FieldValue($name)).to_field_value()),
+                )*];
+                promoted.append(&mut fields);
+                (message, promoted)
+            }
+        }
     }};
+    ($fmt:literal $($rest:tt)*) => {
+        $crate::__log_parts!(@args $fmt [] [] $($rest)*)
+    };
 }
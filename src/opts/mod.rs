@@ -1,34 +1,192 @@
-use std::path::PathBuf;
+use std::{
+    io,
+    path::PathBuf,
+    sync::{atomic::Ordering, Arc},
+};
 
 use crate::{
-    logger::{LogInterval, Logger},
-    DEBUG, LOGGER,
+    capture,
+    format::LogFormat,
+    logger::{Compression, FileNaming, LogInterval, LogLevel, Logger, OverflowPolicy, Retention},
+    read,
+    record::LogRecord,
+    sinks::{ActiveSink, ConsoleSink, Sink},
+    timezone::Timezone,
+    try_read, write, BORDERS, COLORS, CONSOLE_FORMAT, CONSOLE_LEVEL, DEBUG, LOGGER, SINKS, SYMBOLS,
+    TEXT_STYLING, TIMEZONE,
 };
 
 pub mod customize;
 pub mod messages;
 
+/// Runs `f` on the global logger, `None` if it has not been initialized
+fn with_logger<R>(f: impl FnOnce(&mut Logger) -> R) -> Option<R> {
+    write(&LOGGER).as_mut().map(f)
+}
+
+fn not_initialized() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        "the logger has not been initialized, call `init_logger` first",
+    )
+}
+
 /// Initializes the logger
 ///
 /// Logs are generated based on the current day.
 /// All logs are stored in the directory specified by `path`.
 /// With the name format of `YYYY-MM-DD.log`.
+///
+/// Calling it again replaces the logger, after writing out anything the old one
+/// had queued. Settings made on the old logger don't carry over.
 #[inline]
-pub fn init_logger<P: Into<PathBuf>>(path: P, log_interval: LogInterval) -> std::io::Result<()> {
-    unsafe {
-        let logger = Logger::new(path, log_interval)?;
-        LOGGER.set(logger).unwrap_or(());
-    }
+pub fn init_logger<P: Into<PathBuf>>(path: P, log_interval: LogInterval) -> io::Result<()> {
+    let mut logger = Logger::new(path, log_interval)?;
+    logger.set_timezone(*read(&TIMEZONE));
+    let previous = write(&LOGGER).replace(logger);
+    // Dropped outside of the lock, waiting on its background writer can take a while
+    drop(previous);
     Ok(())
 }
 
+/// Puts every global setting back to its default
+///
+/// The logger is removed, the sinks are back to just the console, and the console
+/// level, format, time zone, debug, text styling, colors, symbols and borders are
+/// restored. Meant for tests that change global settings.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_debug(false);
+/// text_styling_off();
+///
+/// reset();
+/// ```
+pub fn reset() {
+    let logger = write(&LOGGER).take();
+    drop(logger);
+    let sinks = std::mem::replace(
+        &mut *write(&SINKS),
+        vec![Box::new(ConsoleSink::new()) as Box<dyn Sink>],
+    );
+    drop(sinks);
+
+    DEBUG.store(true, Ordering::Relaxed);
+    TEXT_STYLING.store(true, Ordering::Relaxed);
+    *write(&CONSOLE_LEVEL) = LogLevel::Trace;
+    *write(&CONSOLE_FORMAT) = None;
+    *write(&TIMEZONE) = Timezone::default();
+    *write(&COLORS) = Arc::default();
+    *write(&SYMBOLS) = Arc::default();
+    *write(&BORDERS) = Arc::default();
+}
+
 /// Debug is enabled by default
 ///
 /// If set to false all `debug` macros will not be printed to the console, but will
 /// still be logged to the log file.
 #[inline]
 pub fn set_debug(debug: bool) {
-    DEBUG.set(debug).unwrap_or(());
+    DEBUG.store(debug, Ordering::Relaxed);
+}
+
+/// All levels are printed to the console by default
+///
+/// Only records of `level` or a more severe one are printed once set.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// set_console_level(LogLevel::Info);
+/// debug!("Processing", "Items in queue: {}", 42); // Not printed
+/// success!("Login", "User {} connected", "Alice"); // Printed
+/// ```
+#[inline]
+pub fn set_console_level(level: LogLevel) {
+    *write(&CONSOLE_LEVEL) = level;
+}
+
+/// The console uses the styled tlogger layout by default
+///
+/// Set a format to print records to the console in that format instead.
+/// Like the text format in files, `style_text!` markup is removed rather than styled.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// set_console_format(LogFormat::Logfmt);
+/// warn!("Memory", "Usage at {}%", 85);
+/// // ts=2024-11-20T10:15:02.123+01:00 level=warn title=Memory msg="Usage at 85%"
+/// ```
+#[inline]
+pub fn set_console_format(format: LogFormat) {
+    *write(&CONSOLE_FORMAT) = Some(format);
+}
+
+/// Timestamps and log intervals use the local time zone by default
+///
+/// Sets the time zone for console timestamps and, if the logger has been
+/// initialized, for its file names, interval boundaries and file timestamps.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_timezone(Timezone::Utc);
+/// ```
+#[inline]
+pub fn set_timezone(timezone: Timezone) {
+    *write(&TIMEZONE) = timezone;
+    with_logger(|logger| logger.set_timezone(timezone));
+}
+
+/// Adds a sink every record is passed to, in addition to the existing ones
+///
+/// Records go to the console by default, call [`clear_sinks`] first to replace it.
+/// A `Logger` can be added as a sink to write to more than one log directory.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// // Errors go to errors/ as JSON, everything goes to all/ as text
+/// let mut errors = Logger::new("Logs/errors", LogInterval::OneDay).unwrap();
+/// errors.clear_log_levels();
+/// errors.add_log_level(LogLevel::Error);
+/// errors.set_format(LogFormat::Json);
+/// add_sink(errors);
+/// add_sink(Logger::new("Logs/all", LogInterval::OneDay).unwrap());
+/// ```
+#[inline]
+pub fn add_sink<S: Sink + 'static>(sink: S) {
+    write(&SINKS).push(Box::new(sink));
+}
+
+/// Removes all sinks, including the default console sink
+#[inline]
+pub fn clear_sinks() {
+    write(&SINKS).clear();
+}
+
+/// Writes out anything the sinks have buffered
+///
+/// Every sink is flushed, the first error is returned.
+#[inline]
+pub fn flush_sinks() -> io::Result<()> {
+    let mut result = Ok(());
+    for sink in read(&SINKS).iter() {
+        let _active = ActiveSink::enter(&**sink);
+        if let Err(e) = sink.flush() {
+            result = result.and(Err(e));
+        }
+    }
+    result
 }
 
 /// Clears the list of Log types to save to log files
@@ -39,18 +197,318 @@ pub fn set_debug(debug: bool) {
 /// Like not saving success logs for example as well they have succeeded.
 #[inline]
 pub fn clear_log_levels() {
-    unsafe {
-        _ = LOGGER.get_mut().unwrap().clear_log_levels();
+    with_logger(Logger::clear_log_levels);
+}
+
+/// Adds a log type to the list of log types to save to log files
+///
+/// See [`add_log_levels!`](crate::add_log_levels) to add several at once.
+#[inline]
+pub fn add_log_level(level: LogLevel) {
+    with_logger(|logger| logger.add_log_level(level));
+}
+
+/// Only saves records of `level` or a more severe one to log files
+///
+/// Applies on top of the levels picked with `add_log_levels!`.
+/// Like the other log file settings, does nothing if the logger has not been initialized.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_log_level("warn".parse().unwrap());
+/// ```
+#[inline]
+pub fn set_log_level(level: LogLevel) {
+    with_logger(|logger| logger.set_min_level(level));
+}
+
+/// Sets the format log files are written in
+///
+/// By default files are written as plain text. `LogFormat::Json` writes one
+/// JSON object per line instead, which is easier for log shippers to parse.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_log_format(LogFormat::Json);
+/// ```
+#[inline]
+pub fn set_log_format(format: LogFormat) {
+    with_logger(|logger| logger.set_format(format));
+}
+
+/// Sets how log files are named and laid out in the log directory
+///
+/// Fails if one of the strftime patterns is invalid.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_file_naming(FileNaming {
+///     prefix: Some("my-app-".to_string()),
+///     latest_link: Some("latest.log".to_string()),
+///     ..Default::default()
+/// })
+/// .unwrap();
+/// ```
+#[inline]
+pub fn set_file_naming(naming: FileNaming) -> io::Result<()> {
+    with_logger(|logger| logger.set_file_naming(naming)).unwrap_or_else(|| Err(not_initialized()))
+}
+
+/// Rotates log files when they would grow past `bytes`, in addition to the log interval
+///
+/// Files of the same interval are numbered, `2024-11-20-10h-11h.log` is followed
+/// by `2024-11-20-10h-11h.1.log`, `2024-11-20-10h-11h.2.log` and so on.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_max_file_size(100 * 1024 * 1024); // 100 MiB
+/// ```
+#[inline]
+pub fn set_max_file_size(bytes: u64) {
+    with_logger(|logger| logger.set_max_file_size(bytes));
+}
+
+/// Starts a new numbered log file right away
+///
+/// Does nothing if the logger has not been initialized.
+#[inline]
+pub fn rotate_now() -> io::Result<()> {
+    match read(&LOGGER).as_ref() {
+        Some(logger) => logger.rotate_now(),
+        None => Ok(()),
     }
 }
 
+/// Sets limits on the age, number and total size of the files kept in the log directory
+///
+/// Files outside of these limits are removed whenever the logger moves on to a new file.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_retention(Retention {
+///     max_age_days: Some(14),
+///     max_files: Some(100),
+///     ..Default::default()
+/// });
+/// ```
+#[inline]
+pub fn set_retention(retention: Retention) {
+    with_logger(|logger| logger.set_retention(retention));
+}
+
+/// Compresses log files once the logger has moved on to a new file
+///
+/// Compression runs on a background thread. Files left uncompressed by an earlier
+/// run are compressed once the logger writes its first record.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_compression(Compression::Gzip).unwrap();
+/// ```
+#[inline]
+pub fn set_compression(compression: Compression) -> io::Result<()> {
+    with_logger(|logger| logger.set_compression(compression))
+        .unwrap_or_else(|| Err(not_initialized()))
+}
+
+/// Stops writing debug, info and success logs to disk once free disk space drops below `bytes`
+///
+/// A single warning is written to the log file when this happens, warnings and errors are still written.
+#[inline]
+pub fn set_min_free_disk(bytes: u64) {
+    with_logger(|logger| logger.set_min_free_disk(bytes));
+}
+
+/// Writes log files on a dedicated thread, so logging never waits on the disk
+///
+/// At most `capacity` records wait to be written, `overflow` decides what happens
+/// to records logged while the queue is full. Queued records are written when the
+/// program exits normally, call [`flush_logger`] to wait for them at any other point.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_async_logging(10_000, OverflowPolicy::DropOldest).unwrap();
+///
+/// info!("Server", "Starting");
+/// flush_logger();
+/// ```
+#[inline]
+pub fn set_async_logging(capacity: usize, overflow: OverflowPolicy) -> io::Result<()> {
+    with_logger(|logger| logger.set_async(capacity, overflow))
+        .unwrap_or_else(|| Err(not_initialized()))
+}
+
+/// Shutdown steps of background threads, run at exit after the flushes
+static EXIT_HOOKS: std::sync::Mutex<Vec<fn()>> = std::sync::Mutex::new(Vec::new());
+
+/// Flushes the sinks and the logger when the program exits normally
+///
+/// Called by everything that writes records on a background thread, the exit
+/// handler is only registered once.
+pub(crate) fn flush_at_exit() {
+    #[cfg(unix)]
+    {
+        static REGISTER: std::sync::Once = std::sync::Once::new();
+
+        extern "C" fn on_exit() {
+            _ = flush_sinks();
+            flush_logger();
+            let hooks = EXIT_HOOKS.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            for hook in hooks {
+                hook();
+            }
+        }
+
+        REGISTER.call_once(|| unsafe {
+            libc::atexit(on_exit);
+        });
+    }
+}
+
+/// Runs `hook` when the program exits normally, after the sinks and the logger are flushed
+pub(crate) fn at_exit(hook: fn()) {
+    EXIT_HOOKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(hook);
+    flush_at_exit();
+}
+
+/// Waits until all records queued for the log files have been written
+///
+/// Returns right away if the logger isn't writing on a background thread.
+#[inline]
+pub fn flush_logger() {
+    if let Some(logger) = read(&LOGGER).as_ref() {
+        let _active = ActiveSink::enter(logger);
+        logger.flush();
+    }
+}
+
+/// Logs panics as error boxes before the previous panic hook runs
+///
+/// The record carries the panic message, the thread name and the source location,
+/// plus a backtrace if `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enables one. It goes
+/// to every sink and the log file like any other record, after which the sinks and
+/// the logger are flushed so nothing is lost if the panic ends the program. The
+/// message is shown as is, without `style_text!` markup applied.
+///
+/// The hook never waits on a lock: sinks that are locked, or that the panic
+/// happened in, are skipped.
+///
+/// Installing the hook more than once has no effect.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// install_panic_hook();
+/// ```
+pub fn install_panic_hook() {
+    static INSTALL: std::sync::Once = std::sync::Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            thread_local! {
+                static IN_HOOK: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+            }
+
+            // A panic while logging a panic goes straight to the previous hook
+            if !IN_HOOK.replace(true) {
+                log_panic(&panic_record(info));
+                IN_HOOK.set(false);
+            }
+            previous(info);
+        }));
+    });
+}
+
+/// Logs a panic record to the sinks and the log file and flushes them, like
+/// `log_record`, `flush_sinks` and `flush_logger` but without waiting on any lock
+fn log_panic(record: &LogRecord) {
+    capture::capture(record);
+
+    if let Some(sinks) = try_read(&SINKS) {
+        for sink in sinks.iter() {
+            if ActiveSink::is(&**sink) || !sink.enabled(&record.level) {
+                continue;
+            }
+            let _active = ActiveSink::enter(&**sink);
+            if let Err(e) = sink.log(record).and_then(|()| sink.flush()) {
+                eprintln!("Error writing to log sink: {e}");
+            }
+        }
+    }
+
+    if let Some(logger) = try_read(&LOGGER) {
+        if let Some(logger) = logger.as_ref().filter(|logger| !ActiveSink::is(*logger)) {
+            let _active = ActiveSink::enter(logger);
+            if let Err(e) = logger.try_log(record) {
+                eprintln!("Error logging to file: {e}");
+            }
+            logger.flush();
+        }
+    }
+}
+
+/// The error record logged for a panic
+fn panic_record(info: &std::panic::PanicHookInfo<'_>) -> LogRecord {
+    let payload = info.payload();
+    let mut message = match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "Box<dyn Any>".to_string(),
+        },
+    };
+
+    let backtrace = std::backtrace::Backtrace::capture();
+    if backtrace.status() == std::backtrace::BacktraceStatus::Captured {
+        message.push_str(&format!("\n\nBacktrace:\n{}", backtrace));
+    }
+
+    let thread = std::thread::current();
+    let mut fields = vec![("thread", thread.name().unwrap_or("<unnamed>").to_string())];
+    if let Some(location) = info.location() {
+        fields.push(("location", location.to_string()));
+    }
+
+    LogRecord::new(LogLevel::Error, "Panic", message)
+        .with_fields(fields)
+        .boxed(true)
+        .raw(true)
+}
+
 /// Adds log types to the list of log types to save to log files
 ///
 /// Only save the logs of the types you need!
 ///
 /// # Example
-/// ```rust
-/// use t_logger::prelude::*;
+/// ```rust,no_run
+/// use tlogger::prelude::*;
 ///
 /// init_logger("Logs", LogInterval::OneHour).unwrap();
 /// clear_log_levels(); // Want to clear always, because by default all log types are saved
@@ -59,10 +517,8 @@ pub fn clear_log_levels() {
 #[macro_export]
 macro_rules! add_log_levels {
     ($($level:expr),+ $(,)?) => {
-        unsafe {
-            $(
-                _ = LOGGER.get_mut().unwrap().add_log_level($level);
-            )+
-        }
+        $(
+            $crate::opts::add_log_level($level);
+        )+
     };
 }
@@ -1,8 +1,27 @@
 pub use crate::{
-    add_log_levels, create_styled_box, debug, debug_box, error, error_box, get_borders, get_colors,
-    get_symbols, get_text_styling, info, info_box, logger::LogInterval, logger::LogLevel, make_log,
-    opts::clear_log_levels, opts::customize::customize_borders, opts::customize::customize_colors,
-    opts::customize::customize_symbols, opts::customize::strip_ansi_codes, opts::init_logger,
-    opts::set_debug, style_text, success, success_box, text_styling_off, text_styling_on, warn,
-    warn_box, LOGGER,
+    add_log_levels, assert_logged, assert_not_logged, capture::capture_logs, capture::LogCapture,
+    create_log_line, create_styled_box, create_styled_box_with_fields, debug, debug_box, error,
+    error_box, filter::Directives, format::LogFormat, get_borders, get_colors, get_symbols,
+    get_text_styling, info, info_box, instance::TLogger, log_record, logger::read_log_file,
+    logger::Compression, logger::FileNaming, logger::LogInterval, logger::LogLevel, logger::Logger,
+    logger::OverflowPolicy, logger::Retention, make_log, opts::add_log_level, opts::add_sink,
+    opts::clear_log_levels, opts::clear_sinks, opts::customize::customize_borders,
+    opts::customize::customize_colors, opts::customize::customize_symbols,
+    opts::customize::strip_ansi_codes, opts::customize::strip_style_markup, opts::flush_logger,
+    opts::flush_sinks, opts::init_logger, opts::install_panic_hook, opts::reset, opts::rotate_now,
+    opts::set_async_logging, opts::set_compression, opts::set_console_format,
+    opts::set_console_level, opts::set_debug, opts::set_file_naming, opts::set_log_format,
+    opts::set_log_level, opts::set_max_file_size, opts::set_min_free_disk, opts::set_retention,
+    opts::set_timezone, record::LogRecord, sinks::ConsoleSink, sinks::Facility, sinks::Filtered,
+    sinks::Formatted, sinks::MemorySink, sinks::NetworkSink, sinks::RingBufferSink, sinks::Sink,
+    sinks::SinkFormat, sinks::SyslogFormat, sinks::SyslogSink, sinks::WriterSink, style_text,
+    success, success_box, text_styling_off, text_styling_on, timezone::Timezone, trace, trace_box,
+    warn, warn_box, LOGGER,
 };
+
+#[cfg(feature = "log")]
+pub use crate::log_facade::install_log_facade;
+#[cfg(target_os = "linux")]
+pub use crate::sinks::JournaldSink;
+#[cfg(feature = "tracing")]
+pub use crate::tracing_layer::TLoggerLayer;
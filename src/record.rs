@@ -0,0 +1,92 @@
+use chrono::{DateTime, Local};
+
+use crate::{
+    fields::Fields,
+    logger::LogLevel,
+    opts::customize::{strip_ansi_codes, strip_style_markup},
+};
+
+/// The place in the source code a log record was created
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Callsite {
+    pub module_path: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+}
+
+impl Callsite {
+    pub fn new(module_path: &'static str, file: &'static str, line: u32) -> Self {
+        Self {
+            module_path,
+            file,
+            line,
+        }
+    }
+}
+
+/// A single log event, as created by the logging macros
+///
+/// The console and the log files each format the record on their own, so
+/// file output does not depend on the configured colors or symbols.
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub title: String,
+    /// The formatted message, still containing any `style_text!` markup
+    pub message: String,
+    pub timestamp: DateTime<Local>,
+    pub fields: Fields,
+    pub callsite: Option<Callsite>,
+    /// Whether the record is shown as a box on the console
+    pub boxed: bool,
+    /// Whether the message is shown as is, without `style_text!` markup being applied,
+    /// as for records from other crates
+    pub raw: bool,
+}
+
+impl LogRecord {
+    /// Creates a record timestamped now, without fields or callsite
+    pub fn new<T: Into<String>, M: Into<String>>(level: LogLevel, title: T, message: M) -> Self {
+        Self {
+            level,
+            title: title.into(),
+            message: message.into(),
+            timestamp: Local::now(),
+            fields: Fields::new(),
+            callsite: None,
+            boxed: false,
+            raw: false,
+        }
+    }
+
+    pub fn with_fields(mut self, fields: Fields) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    pub fn with_callsite(mut self, callsite: Callsite) -> Self {
+        self.callsite = Some(callsite);
+        self
+    }
+
+    /// Show this record as a box on the console
+    pub fn boxed(mut self, boxed: bool) -> Self {
+        self.boxed = boxed;
+        self
+    }
+
+    /// Leave `**`, `_` and the other `style_text!` markers in the message alone
+    pub fn raw(mut self, raw: bool) -> Self {
+        self.raw = raw;
+        self
+    }
+
+    /// The message without markup or ANSI codes, as written to log files
+    pub fn plain_message(&self) -> String {
+        if self.raw {
+            strip_ansi_codes(&self.message)
+        } else {
+            strip_style_markup(&self.message)
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::{io, sync::atomic::Ordering};
+
+use crate::{
+    capture::console_quiet, filter::console_directives, logger::LogLevel, read, record::LogRecord,
+    CONSOLE_LEVEL, DEBUG,
+};
+
+use super::{Filtered, Formatted, LevelFilter, Output, Sink, SinkFormat};
+
+#[derive(Debug, Clone, Copy)]
+enum Stream {
+    /// Errors to stderr, everything else to stdout
+    Auto,
+    Stdout,
+    Stderr,
+}
+
+/// Prints records to the console
+///
+/// A `ConsoleSink::new()` is registered by default, printing errors to stderr and
+/// everything else to stdout. Trace and debug records are only printed while debug
+/// is enabled, see `set_debug`. The threshold of `set_console_level` and the
+/// `TLOGGER_CONSOLE` directives apply to all console sinks.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// clear_sinks();
+/// add_sink(ConsoleSink::stdout().with_levels([LogLevel::Info, LogLevel::Success]));
+/// add_sink(
+///     ConsoleSink::stderr()
+///         .with_levels([LogLevel::Warn, LogLevel::Error])
+///         .with_format(SinkFormat::Json),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConsoleSink {
+    stream: Stream,
+    output: Output,
+}
+
+impl ConsoleSink {
+    /// Prints errors to stderr and everything else to stdout, in the styled layout
+    pub fn new() -> Self {
+        Self::with_stream(Stream::Auto)
+    }
+
+    /// Prints everything to stdout, in the styled layout
+    pub fn stdout() -> Self {
+        Self::with_stream(Stream::Stdout)
+    }
+
+    /// Prints everything to stderr, in the styled layout
+    pub fn stderr() -> Self {
+        Self::with_stream(Stream::Stderr)
+    }
+
+    fn with_stream(stream: Stream) -> Self {
+        Self {
+            stream,
+            output: Output::new(SinkFormat::Styled),
+        }
+    }
+}
+
+impl Default for ConsoleSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `set_debug` and `set_console_level` let records of this level through
+fn global_enabled(level: &LogLevel) -> bool {
+    (*level > LogLevel::Debug || DEBUG.load(Ordering::Relaxed)) && *level >= *read(&CONSOLE_LEVEL)
+}
+
+impl Filtered for ConsoleSink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.output.filter
+    }
+}
+
+impl Formatted for ConsoleSink {
+    fn format_mut(&mut self) -> &mut SinkFormat {
+        &mut self.output.format
+    }
+}
+
+impl Sink for ConsoleSink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        // A directive may let through what the global settings filter out, `log`
+        // checks the record itself
+        self.output.enabled(level)
+            && (global_enabled(level) || console_directives().may_enable(level))
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        if console_quiet()
+            || !console_directives().enabled_or(record, || global_enabled(&record.level))
+        {
+            return Ok(());
+        }
+
+        let output = self.output.format.format(record);
+        match (self.stream, &record.level) {
+            (Stream::Stderr, _) | (Stream::Auto, LogLevel::Error) => eprint!("{output}"),
+            _ => print!("{output}"),
+        }
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        use std::io::Write;
+
+        io::stdout().flush()?;
+        io::stderr().flush()
+    }
+}
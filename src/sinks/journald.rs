@@ -0,0 +1,186 @@
+use std::{
+    fs::File,
+    io::{self, Write},
+    os::{
+        fd::{AsRawFd, FromRawFd, RawFd},
+        unix::net::UnixDatagram,
+    },
+    path::Path,
+};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+use super::{syslog::app_name, Filtered, LevelFilter, Sink};
+
+/// The socket journald listens on for the native protocol
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+/// Prefix of the journal fields holding record fields
+const FIELD_PREFIX: &str = "TLOGGER_F_";
+
+/// Sends records to the systemd journal, using its native protocol
+///
+/// Levels map to `PRIORITY` like syslog severities, see `LogLevel::syslog_severity`.
+/// The title goes to `TLOGGER_TITLE` and fields to journal fields of their own, with
+/// uppercase names prefixed by `TLOGGER_F_`. Records too large for a datagram are passed in a sealed memfd.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// clear_sinks();
+/// add_sink(JournaldSink::new().unwrap().with_identifier("my-app"));
+///
+/// warn!("Memory", "Usage at {usage}%", usage = 85);
+/// // journalctl -t my-app TLOGGER_TITLE=Memory TLOGGER_F_USAGE=85
+/// ```
+pub struct JournaldSink {
+    socket: UnixDatagram,
+    identifier: String,
+    filter: LevelFilter,
+}
+
+impl JournaldSink {
+    /// Sends to the journal of this host
+    pub fn new() -> io::Result<Self> {
+        Self::at(JOURNAL_SOCKET)
+    }
+
+    /// Sends to a journal listening on the socket at `path`
+    pub fn at<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self {
+            socket,
+            identifier: app_name(),
+            filter: LevelFilter::default(),
+        })
+    }
+
+    /// The `SYSLOG_IDENTIFIER` of the entries, the name of the executable by default
+    pub fn with_identifier<S: Into<String>>(mut self, identifier: S) -> Self {
+        self.identifier = identifier.into();
+        self
+    }
+
+    /// Serializes a record as a journal entry
+    fn entry(&self, record: &LogRecord) -> Vec<u8> {
+        let mut entry = Vec::new();
+        append_field(&mut entry, "MESSAGE", &record.plain_message());
+        append_field(
+            &mut entry,
+            "PRIORITY",
+            &record.level.syslog_severity().to_string(),
+        );
+        append_field(&mut entry, "SYSLOG_IDENTIFIER", &self.identifier);
+        append_field(&mut entry, "TLOGGER_TITLE", &record.title);
+        append_field(&mut entry, "TLOGGER_LEVEL", record.level.as_str());
+        if let Some(callsite) = &record.callsite {
+            append_field(&mut entry, "CODE_FILE", callsite.file);
+            append_field(&mut entry, "CODE_LINE", &callsite.line.to_string());
+            append_field(&mut entry, "TLOGGER_MODULE", callsite.module_path);
+        }
+        for (key, value) in &record.fields {
+            append_field(&mut entry, &field_name(key), value);
+        }
+        entry
+    }
+}
+
+impl Filtered for JournaldSink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.filter
+    }
+}
+
+impl Sink for JournaldSink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.filter.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let entry = self.entry(record);
+        match self.socket.send(&entry) {
+            Ok(_) => Ok(()),
+            Err(e) if e.raw_os_error() == Some(libc::EMSGSIZE) => {
+                send_in_memfd(&self.socket, &entry)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Appends a `NAME=value` line, or the length-prefixed form if the value spans lines
+fn append_field(entry: &mut Vec<u8>, name: &str, value: &str) {
+    entry.extend_from_slice(name.as_bytes());
+    if value.contains('\n') {
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
+
+/// A journal field name for a record field, `TLOGGER_F_` followed by its name in
+/// uppercase letters, digits and underscores
+///
+/// The prefix keeps fields such as `message` or `priority` from replacing the fields
+/// of the entry itself. Leading underscores are dropped, journal field names are at
+/// most 64 characters long.
+fn field_name(key: &str) -> String {
+    let name = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+            _ => '_',
+        })
+        .skip_while(|&c| c == '_');
+    FIELD_PREFIX.chars().chain(name).take(64).collect()
+}
+
+/// Writes an entry to a sealed memfd and passes the descriptor to journald
+fn send_in_memfd(socket: &UnixDatagram, entry: &[u8]) -> io::Result<()> {
+    let fd = unsafe {
+        libc::memfd_create(
+            c"tlogger-journal".as_ptr(),
+            libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING,
+        )
+    };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let mut file = unsafe { File::from_raw_fd(fd) };
+    file.write_all(entry)?;
+
+    // journald only accepts memfds that can't change anymore
+    let seals = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_WRITE | libc::F_SEAL_SEAL;
+    if unsafe { libc::fcntl(fd, libc::F_ADD_SEALS, seals) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    send_fd(socket, file.as_raw_fd())
+}
+
+/// Sends an empty datagram carrying `fd`
+fn send_fd(socket: &UnixDatagram, fd: RawFd) -> io::Result<()> {
+    let fd_len = std::mem::size_of::<RawFd>() as libc::c_uint;
+    let mut control = vec![0u8; unsafe { libc::CMSG_SPACE(fd_len) } as usize];
+
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = control.len() as _;
+
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(fd_len) as _;
+        std::ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+
+        if libc::sendmsg(socket.as_raw_fd(), &msg, libc::MSG_NOSIGNAL) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use std::{
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+use super::{Filtered, Formatted, LevelFilter, Output, Sink, SinkFormat};
+
+/// Keeps rendered records in memory
+///
+/// Clones share the same buffer, so a clone can be kept to read the records of a
+/// sink that was added to the registry.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let memory = MemorySink::new().with_format(SinkFormat::Text);
+/// add_sink(memory.clone());
+///
+/// warn!("Memory", "Usage at {}%", 85);
+/// assert!(memory.lines().iter().any(|line| line.contains("│ Memory Usage at 85%")));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemorySink {
+    lines: Arc<Mutex<Vec<String>>>,
+    output: Output,
+}
+
+impl MemorySink {
+    /// Keeps records of all levels as plain text lines
+    pub fn new() -> Self {
+        Self {
+            lines: Arc::default(),
+            output: Output::new(SinkFormat::Text),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.lines.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The rendered records so far, one entry per record, without trailing newlines
+    pub fn lines(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Removes all records kept so far
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl Default for MemorySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filtered for MemorySink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.output.filter
+    }
+}
+
+impl Formatted for MemorySink {
+    fn format_mut(&mut self) -> &mut SinkFormat {
+        &mut self.output.format
+    }
+}
+
+impl Sink for MemorySink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.output.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let output = self.output.format.format(record);
+        self.lock()
+            .push(output.strip_suffix('\n').unwrap_or(&output).to_string());
+        Ok(())
+    }
+}
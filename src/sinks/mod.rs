@@ -0,0 +1,207 @@
+use std::{cell::Cell, io, ptr};
+
+use crate::{format::LogFormat, format_console, get_timezone, logger::LogLevel, record::LogRecord};
+
+pub use console::ConsoleSink;
+#[cfg(target_os = "linux")]
+pub use journald::JournaldSink;
+pub use memory::MemorySink;
+pub use network::NetworkSink;
+pub use ring::{RecordQuery, RingBufferSink};
+pub use syslog::{Facility, SyslogFormat, SyslogSink};
+pub use writer::WriterSink;
+
+mod console;
+#[cfg(target_os = "linux")]
+mod journald;
+mod memory;
+mod network;
+mod ring;
+mod syslog;
+mod writer;
+
+/// A destination for log records
+///
+/// Sinks are added with `add_sink`, every record logged is passed to each sink that
+/// has its level enabled. A `Logger` is a sink as well, writing to its own directory.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// struct Counter(std::sync::atomic::AtomicUsize);
+///
+/// impl Sink for Counter {
+///     fn log(&self, _record: &LogRecord) -> std::io::Result<()> {
+///         self.0.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+///         Ok(())
+///     }
+/// }
+///
+/// add_sink(Counter(Default::default()));
+/// ```
+pub trait Sink: Send + Sync {
+    /// Whether records of this level are passed to the sink
+    fn enabled(&self, _level: &LogLevel) -> bool {
+        true
+    }
+
+    /// Writes a record to the sink
+    fn log(&self, record: &LogRecord) -> io::Result<()>;
+
+    /// Writes out anything the sink has buffered
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    /// The sink this thread is writing to or flushing
+    static ACTIVE: Cell<*const ()> = const { Cell::new(ptr::null()) };
+}
+
+/// Marks a sink as the one the current thread is using, until dropped
+///
+/// A panic inside the sink runs the panic hook with the sink's locks still held, so
+/// the hook skips the active sink instead of logging to it again.
+pub(crate) struct ActiveSink(*const ());
+
+impl ActiveSink {
+    pub(crate) fn enter(sink: &dyn Sink) -> Self {
+        Self(ACTIVE.replace(address(sink)))
+    }
+
+    /// Whether the current thread is using `sink`
+    pub(crate) fn is(sink: &dyn Sink) -> bool {
+        ACTIVE.get() == address(sink)
+    }
+}
+
+impl Drop for ActiveSink {
+    fn drop(&mut self) {
+        ACTIVE.set(self.0);
+    }
+}
+
+fn address(sink: &dyn Sink) -> *const () {
+    sink as *const dyn Sink as *const ()
+}
+
+/// How a sink renders records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SinkFormat {
+    /// The styled console layout, with colors, symbols and boxes
+    #[default]
+    Styled,
+    /// Plain text lines, as written to `LogFormat::Text` files
+    Text,
+    /// One JSON object per line
+    Json,
+    /// logfmt lines
+    Logfmt,
+}
+
+impl SinkFormat {
+    /// Renders a record, including the trailing newline
+    pub fn format(&self, record: &LogRecord) -> String {
+        let format = match self {
+            SinkFormat::Styled => return format_console(record),
+            SinkFormat::Text => LogFormat::Text,
+            SinkFormat::Json => LogFormat::Json,
+            SinkFormat::Logfmt => LogFormat::Logfmt,
+        };
+        format.format_with_timezone(record, get_timezone())
+    }
+}
+
+/// The levels a sink or `TLogger` passes on, set with the methods of [`Filtered`]
+#[derive(Debug, Clone)]
+pub struct LevelFilter {
+    /// The levels passed on, all of them if `None`
+    levels: Option<Vec<LogLevel>>,
+    /// The least severe level passed on
+    min_level: LogLevel,
+}
+
+impl Default for LevelFilter {
+    fn default() -> Self {
+        Self {
+            levels: None,
+            min_level: LogLevel::Trace,
+        }
+    }
+}
+
+impl LevelFilter {
+    pub(crate) fn enabled(&self, level: &LogLevel) -> bool {
+        *level >= self.min_level
+            && self
+                .levels
+                .as_ref()
+                .is_none_or(|levels| levels.contains(level))
+    }
+}
+
+/// Builder methods for the levels a sink or `TLogger` passes on
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let errors = MemorySink::new().with_min_level(LogLevel::Error);
+/// let logins = RingBufferSink::new(100).with_levels([LogLevel::Success]);
+/// ```
+pub trait Filtered: Sized {
+    /// The filter the builder methods change
+    fn filter_mut(&mut self) -> &mut LevelFilter;
+
+    /// Only pass on records of these levels
+    fn with_levels<I: IntoIterator<Item = LogLevel>>(mut self, levels: I) -> Self {
+        self.filter_mut().levels = Some(levels.into_iter().collect());
+        self
+    }
+
+    /// Only pass on records of this level or a more severe one
+    fn with_min_level(mut self, level: LogLevel) -> Self {
+        self.filter_mut().min_level = level;
+        self
+    }
+}
+
+/// Builder method for the format a sink renders records in
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// add_sink(WriterSink::new(std::io::stderr()).with_format(SinkFormat::Json));
+/// ```
+pub trait Formatted: Sized {
+    /// The format the builder method changes
+    fn format_mut(&mut self) -> &mut SinkFormat;
+
+    fn with_format(mut self, format: SinkFormat) -> Self {
+        *self.format_mut() = format;
+        self
+    }
+}
+
+/// The level filter and format of the sinks in this module that render records
+#[derive(Debug, Clone)]
+struct Output {
+    filter: LevelFilter,
+    format: SinkFormat,
+}
+
+impl Output {
+    fn new(format: SinkFormat) -> Self {
+        Self {
+            filter: LevelFilter::default(),
+            format,
+        }
+    }
+
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.filter.enabled(level)
+    }
+}
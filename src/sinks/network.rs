@@ -0,0 +1,540 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    mem,
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    path::{Path, PathBuf},
+    sync::{Arc, Condvar, Mutex, MutexGuard, Once, Weak},
+    thread::{self, JoinHandle, ThreadId},
+    time::{Duration, Instant},
+};
+
+use crate::{logger::LogLevel, opts::at_exit, record::LogRecord};
+
+use super::{Filtered, Formatted, LevelFilter, Output, Sink, SinkFormat};
+
+/// How long connecting to the collector may take
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long a write to the collector may block before the connection is given up
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The queues of the running shipper threads, shut down when the program exits
+static SHIPPERS: Mutex<Vec<Weak<Queue>>> = Mutex::new(Vec::new());
+
+/// Ships records to a collector as newline-delimited JSON, over TCP or UDP
+///
+/// Over TCP, records are queued and sent by a background thread, so logging never
+/// waits on the network. When the collector can't be reached the thread reconnects
+/// with exponential backoff, keeping records in a bounded queue or, with
+/// `with_disk_spool`, in a file until they can be sent. Records are sent at least
+/// once, a connection lost halfway through can cause a few to be sent twice. Records
+/// still queued when the program exits normally are sent, or spooled if the
+/// collector can't be reached.
+///
+/// Over UDP, each record is sent as a datagram right away.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// add_sink(
+///     NetworkSink::tcp("collector.internal:5170")
+///         .unwrap()
+///         .with_min_level(LogLevel::Info)
+///         .with_disk_spool("Logs/collector.spool"),
+/// );
+/// ```
+pub struct NetworkSink {
+    transport: Transport,
+    output: Output,
+}
+
+enum Transport {
+    Udp(UdpSocket),
+    Tcp {
+        settings: Settings,
+        /// Spawned with the first record, so the builder methods apply to it
+        shipper: Mutex<Option<Shipper>>,
+    },
+}
+
+#[derive(Clone)]
+struct Settings {
+    addr: SocketAddr,
+    /// The most records kept in memory while the collector is unreachable
+    capacity: usize,
+    spool: Option<PathBuf>,
+    /// The largest size the spool file may grow to, in bytes
+    spool_limit: u64,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    /// The format of the sink, set when the shipper is spawned
+    format: SinkFormat,
+}
+
+impl NetworkSink {
+    /// Streams records to a collector over TCP
+    ///
+    /// The collector doesn't have to be reachable yet, the connection is made in
+    /// the background.
+    pub fn tcp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Self {
+            transport: Transport::Tcp {
+                settings: Settings {
+                    addr: resolve(addr)?,
+                    capacity: 10_000,
+                    spool: None,
+                    spool_limit: 64 * 1024 * 1024,
+                    initial_backoff: Duration::from_millis(100),
+                    max_backoff: Duration::from_secs(30),
+                    format: SinkFormat::Json,
+                },
+                shipper: Mutex::new(None),
+            },
+            output: Output::new(SinkFormat::Json),
+        })
+    }
+
+    /// Sends a datagram per record to a collector
+    pub fn udp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addr = resolve(addr)?;
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(Self {
+            transport: Transport::Udp(socket),
+            output: Output::new(SinkFormat::Json),
+        })
+    }
+
+    /// Keeps at most `capacity` records in memory while the collector is unreachable,
+    /// dropping the oldest ones beyond that
+    ///
+    /// 10 000 by default, only used over TCP.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        if let Transport::Tcp { settings, .. } = &mut self.transport {
+            settings.capacity = capacity.max(1);
+        }
+        self
+    }
+
+    /// Appends records to the file at `path` while the collector is unreachable, and
+    /// sends them once it is back
+    ///
+    /// Records a previous run left in the file are sent first. Only used over TCP.
+    pub fn with_disk_spool<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        if let Transport::Tcp { settings, .. } = &mut self.transport {
+            settings.spool = Some(path.into());
+        }
+        self
+    }
+
+    /// Lets the spool file grow to at most `bytes`, keeping further records in memory
+    /// as if there was no spool
+    ///
+    /// 64 MiB by default.
+    pub fn with_spool_limit(mut self, bytes: u64) -> Self {
+        if let Transport::Tcp { settings, .. } = &mut self.transport {
+            settings.spool_limit = bytes;
+        }
+        self
+    }
+
+    /// Waits `initial` after the first failed connection attempt, doubling the wait
+    /// after every further failure up to `max`
+    ///
+    /// 100 ms up to 30 s by default, only used over TCP.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        if let Transport::Tcp { settings, .. } = &mut self.transport {
+            settings.initial_backoff = initial;
+            settings.max_backoff = max.max(initial);
+        }
+        self
+    }
+}
+
+impl Filtered for NetworkSink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.output.filter
+    }
+}
+
+impl Formatted for NetworkSink {
+    fn format_mut(&mut self) -> &mut SinkFormat {
+        &mut self.output.format
+    }
+}
+
+impl Sink for NetworkSink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.output.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let line = self.output.format.format(record);
+        match &self.transport {
+            Transport::Udp(socket) => socket.send(line.as_bytes()).map(|_| ()),
+            Transport::Tcp { settings, shipper } => {
+                let mut shipper = lock(shipper);
+                if shipper.is_none() {
+                    *shipper = Some(Shipper::spawn(Settings {
+                        format: self.output.format,
+                        ..settings.clone()
+                    })?);
+                }
+                shipper
+                    .as_ref()
+                    .expect("shipper was spawned above")
+                    .send(line, settings.capacity);
+                Ok(())
+            }
+        }
+    }
+
+    /// Waits until every queued record has been sent, or the collector turned out
+    /// to be unreachable
+    fn flush(&self) -> io::Result<()> {
+        if let Transport::Tcp { shipper, .. } = &self.transport {
+            if let Some(shipper) = lock(shipper).as_ref() {
+                shipper.flush();
+            }
+        }
+        Ok(())
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn resolve<A: ToSocketAddrs>(addr: A) -> io::Result<SocketAddr> {
+    addr.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the collector address did not resolve",
+        )
+    })
+}
+
+#[derive(Default)]
+struct QueueState {
+    lines: VecDeque<String>,
+    /// Records dropped since the shipper thread last took lines off the queue
+    dropped: u64,
+    /// Whether the shipper thread is sending lines it took off the queue
+    writing: bool,
+    /// Whether the last attempt to reach the collector failed
+    unreachable: bool,
+    shutdown: bool,
+    /// Whether the shipper thread has stopped
+    stopped: bool,
+}
+
+struct Queue {
+    state: Mutex<QueueState>,
+    /// Notified whenever lines are queued or sent, an attempt fails, or on shutdown
+    changed: Condvar,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        lock(&self.state)
+    }
+
+    fn wait<'a>(&self, state: MutexGuard<'a, QueueState>) -> MutexGuard<'a, QueueState> {
+        self.changed.wait(state).unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn wait_until<'a>(
+        &self,
+        state: MutexGuard<'a, QueueState>,
+        deadline: Instant,
+    ) -> MutexGuard<'a, QueueState> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match self.changed.wait_timeout(state, timeout) {
+            Ok((state, _)) => state,
+            Err(e) => e.into_inner().0,
+        }
+    }
+}
+
+/// Sends queued lines to the collector on a dedicated thread
+struct Shipper {
+    queue: Arc<Queue>,
+    handle: Option<JoinHandle<()>>,
+    /// The shipper thread, which must never wait on itself
+    thread: ThreadId,
+}
+
+impl Shipper {
+    fn spawn(settings: Settings) -> io::Result<Self> {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState::default()),
+            changed: Condvar::new(),
+        });
+
+        let thread_queue = Arc::clone(&queue);
+        let handle = thread::Builder::new()
+            .name("tlogger-network".to_string())
+            .spawn(move || {
+                run(&thread_queue, &settings);
+                thread_queue.lock().stopped = true;
+                thread_queue.changed.notify_all();
+            })?;
+
+        // A sink kept in the sinks is never dropped, so it is shut down at exit instead
+        static REGISTER: Once = Once::new();
+        REGISTER.call_once(|| at_exit(shutdown_shippers));
+        let mut shippers = lock(&SHIPPERS);
+        shippers.retain(|queue| queue.strong_count() > 0);
+        shippers.push(Arc::downgrade(&queue));
+        drop(shippers);
+
+        Ok(Self {
+            queue,
+            thread: handle.thread().id(),
+            handle: Some(handle),
+        })
+    }
+
+    /// Queues a line, dropping the oldest one if the queue is full
+    fn send(&self, line: String, capacity: usize) {
+        let mut state = self.queue.lock();
+        if state.lines.len() >= capacity {
+            state.lines.pop_front();
+            state.dropped += 1;
+        }
+        state.lines.push_back(line);
+        drop(state);
+        self.queue.changed.notify_all();
+    }
+
+    fn flush(&self) {
+        if thread::current().id() == self.thread {
+            return;
+        }
+        let mut state = self.queue.lock();
+        while (!state.lines.is_empty() || state.writing) && !state.unreachable {
+            state = self.queue.wait(state);
+        }
+    }
+}
+
+impl Drop for Shipper {
+    fn drop(&mut self) {
+        shutdown(&self.queue);
+        if let Some(handle) = self.handle.take() {
+            _ = handle.join();
+        }
+    }
+}
+
+/// Sends all queued lines and stops the shipper thread, spooling the lines if the
+/// collector can't be reached
+fn shutdown(queue: &Queue) {
+    let mut state = queue.lock();
+    state.shutdown = true;
+    queue.changed.notify_all();
+    while !state.stopped {
+        state = queue.wait(state);
+    }
+}
+
+fn shutdown_shippers() {
+    let shippers: Vec<_> = lock(&SHIPPERS).iter().filter_map(Weak::upgrade).collect();
+    for queue in shippers {
+        shutdown(&queue);
+    }
+}
+
+/// The shipper thread, sending queued lines until shut down with an empty queue
+fn run(queue: &Queue, settings: &Settings) {
+    let mut stream = None;
+    let mut backoff = settings.initial_backoff;
+    // When the next connection attempt is due, after a failed one
+    let mut retry_at: Option<Instant> = None;
+    // Whether the spool file holds lines still to be sent, possibly left by an earlier run
+    let mut spool_pending = settings.spool.as_ref().is_some_and(|spool| spool.exists());
+    // Whether the spool file reached its limit, lines stay in memory until it is sent
+    let mut spool_full = false;
+
+    loop {
+        let (lines, dropped, shutdown) = {
+            let mut state = queue.lock();
+            loop {
+                let due = retry_at.is_none_or(|at| Instant::now() >= at);
+                let queued = !state.lines.is_empty();
+                // With a spool, lines are moved to it right away while waiting to retry
+                if state.shutdown
+                    || (queued && (due || (settings.spool.is_some() && !spool_full)))
+                    || (spool_pending && due)
+                {
+                    break;
+                }
+                state = match retry_at {
+                    Some(at) if queued || spool_pending => queue.wait_until(state, at),
+                    _ => queue.wait(state),
+                };
+            }
+            if state.lines.is_empty() && state.shutdown {
+                return;
+            }
+            state.writing = true;
+            (
+                Vec::from(mem::take(&mut state.lines)),
+                mem::take(&mut state.dropped),
+                state.shutdown,
+            )
+        };
+        // Wake up flushes waiting on the queue
+        queue.changed.notify_all();
+
+        let warning = (dropped > 0).then(|| {
+            settings.format.format(&LogRecord::new(
+                LogLevel::Warn,
+                "Logger",
+                format!(
+                    "Dropped {} log records while the collector was unreachable",
+                    dropped
+                ),
+            ))
+        });
+        let batch: String = warning.iter().chain(&lines).map(String::as_str).collect();
+
+        let due = shutdown || retry_at.is_none_or(|at| Instant::now() >= at);
+        let result = if stream.is_some() || due {
+            send(&mut stream, settings, &batch)
+        } else {
+            Err(io::ErrorKind::NotConnected.into())
+        };
+
+        let mut state = queue.lock();
+        state.writing = false;
+        match result {
+            Ok(()) => {
+                state.unreachable = false;
+                spool_pending = false;
+                spool_full = false;
+                backoff = settings.initial_backoff;
+                retry_at = None;
+            }
+            Err(_) => {
+                stream = None;
+                if due {
+                    state.unreachable = true;
+                    retry_at = Some(Instant::now() + backoff);
+                    backoff = (backoff * 2).min(settings.max_backoff);
+                }
+
+                let spooled = match &settings.spool {
+                    Some(spool) => {
+                        append_to_spool(spool, warning.iter().chain(&lines), settings.spool_limit)
+                            .unwrap_or_else(|e| {
+                                eprintln!("Error spooling log records to {}: {e}", spool.display());
+                                0
+                            })
+                    }
+                    None => 0,
+                };
+                spool_pending |= spooled > 0;
+                spool_full |= spooled < usize::from(warning.is_some()) + lines.len();
+
+                // Put the lines that weren't spooled back in front of anything queued
+                // in the meantime
+                let spooled_lines = match warning {
+                    Some(_) if spooled == 0 => {
+                        state.dropped += dropped;
+                        0
+                    }
+                    Some(_) => spooled - 1,
+                    None => spooled,
+                };
+                for line in lines.into_iter().skip(spooled_lines).rev() {
+                    state.lines.push_front(line);
+                }
+                while state.lines.len() > settings.capacity {
+                    state.lines.pop_front();
+                    state.dropped += 1;
+                }
+                if shutdown {
+                    if let Some(spool) = &settings.spool {
+                        let rest: Vec<String> = state.lines.drain(..).collect();
+                        _ = append_to_spool(spool, &rest, settings.spool_limit);
+                    }
+                    drop(state);
+                    queue.changed.notify_all();
+                    return;
+                }
+            }
+        }
+        drop(state);
+        queue.changed.notify_all();
+    }
+}
+
+/// Sends a batch of lines, connecting first if needed
+///
+/// A new connection starts with the lines left in the spool file.
+fn send(stream: &mut Option<TcpStream>, settings: &Settings, batch: &str) -> io::Result<()> {
+    let connected = match stream {
+        Some(connected) => connected,
+        None => {
+            let connected = TcpStream::connect_timeout(&settings.addr, CONNECT_TIMEOUT)?;
+            connected.set_write_timeout(Some(WRITE_TIMEOUT))?;
+            let connected = stream.insert(connected);
+            if let Some(spool) = &settings.spool {
+                send_spool(connected, spool)?;
+            }
+            connected
+        }
+    };
+    connected.write_all(batch.as_bytes())?;
+    connected.flush()
+}
+
+/// Sends the lines in the spool file and removes it
+fn send_spool(stream: &mut TcpStream, spool: &Path) -> io::Result<()> {
+    let spooled = match fs::read(spool) {
+        Ok(spooled) => spooled,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+    stream.write_all(&spooled)?;
+    fs::remove_file(spool)
+}
+
+/// Appends whole lines to the spool file, as long as it stays within `limit` bytes
+///
+/// Returns how many of the lines were appended.
+fn append_to_spool<'a>(
+    spool: &Path,
+    lines: impl IntoIterator<Item = &'a String>,
+    limit: u64,
+) -> io::Result<usize> {
+    let mut lines = lines.into_iter().peekable();
+    if lines.peek().is_none() {
+        return Ok(0);
+    }
+    if let Some(parent) = spool.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(spool)?;
+
+    let mut len = file.metadata()?.len();
+    let mut appended = String::new();
+    let mut count = 0;
+    for line in lines {
+        len += line.len() as u64;
+        if len > limit {
+            break;
+        }
+        appended.push_str(line);
+        count += 1;
+    }
+    file.write_all(appended.as_bytes())?;
+    Ok(count)
+}
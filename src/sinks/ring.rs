@@ -0,0 +1,207 @@
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+use super::{Filtered, LevelFilter, Sink, SinkFormat};
+
+/// Keeps the most recent records in memory, dropping the oldest once full
+///
+/// Clones share the same buffer, so a clone can be kept to query the records of a
+/// sink that was added to the registry, for a crash report or an admin endpoint.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let recent = RingBufferSink::new(500);
+/// add_sink(recent.clone());
+///
+/// warn!("Memory", "Usage at {}%", 85);
+/// error!("Database", "Connection failed");
+///
+/// let warnings = recent.query().min_level(LogLevel::Warn).title("Memory").records();
+/// assert_eq!(warnings.len(), 1);
+///
+/// let report = recent.query().last(50).export(SinkFormat::Json);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RingBufferSink {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+    capacity: usize,
+    filter: LevelFilter,
+}
+
+impl RingBufferSink {
+    /// Keeps the last `capacity` records of all levels
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+            filter: LevelFilter::default(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<LogRecord>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The records kept, oldest first
+    pub fn records(&self) -> Vec<LogRecord> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// The number of records kept
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes all records kept so far
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Starts a query over the records kept, matching all of them until narrowed down
+    pub fn query(&self) -> RecordQuery<'_> {
+        RecordQuery {
+            sink: self,
+            levels: None,
+            min_level: LogLevel::Trace,
+            title: None,
+            since: None,
+            until: None,
+            last: None,
+        }
+    }
+
+    /// Renders all records kept, see `RecordQuery::export`
+    pub fn export(&self, format: SinkFormat) -> String {
+        self.query().export(format)
+    }
+}
+
+impl Filtered for RingBufferSink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.filter
+    }
+}
+
+impl Sink for RingBufferSink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.filter.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let mut records = self.lock();
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record.clone());
+        Ok(())
+    }
+}
+
+/// A filter over the records of a `RingBufferSink`, built with its `query` method
+///
+/// Every condition narrows the query down further.
+#[derive(Debug, Clone)]
+pub struct RecordQuery<'a> {
+    sink: &'a RingBufferSink,
+    levels: Option<Vec<LogLevel>>,
+    min_level: LogLevel,
+    title: Option<String>,
+    since: Option<DateTime<FixedOffset>>,
+    until: Option<DateTime<FixedOffset>>,
+    last: Option<usize>,
+}
+
+impl RecordQuery<'_> {
+    /// Only records of these levels
+    pub fn levels<I: IntoIterator<Item = LogLevel>>(mut self, levels: I) -> Self {
+        self.levels = Some(levels.into_iter().collect());
+        self
+    }
+
+    /// Only records of this level or a more severe one
+    pub fn min_level(mut self, level: LogLevel) -> Self {
+        self.min_level = level;
+        self
+    }
+
+    /// Only records with exactly this title
+    pub fn title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Only records logged at or after `time`
+    pub fn since<Tz: TimeZone>(mut self, time: DateTime<Tz>) -> Self {
+        self.since = Some(time.fixed_offset());
+        self
+    }
+
+    /// Only records logged before `time`
+    pub fn until<Tz: TimeZone>(mut self, time: DateTime<Tz>) -> Self {
+        self.until = Some(time.fixed_offset());
+        self
+    }
+
+    /// Only the `n` most recent of the matching records
+    pub fn last(mut self, n: usize) -> Self {
+        self.last = Some(n);
+        self
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        record.level >= self.min_level
+            && self
+                .levels
+                .as_ref()
+                .is_none_or(|levels| levels.contains(&record.level))
+            && self
+                .title
+                .as_ref()
+                .is_none_or(|title| record.title == *title)
+            && self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp < until)
+    }
+
+    /// The matching records, oldest first
+    pub fn records(&self) -> Vec<LogRecord> {
+        let records = self.sink.lock();
+        let mut matching: Vec<LogRecord> = records
+            .iter()
+            .filter(|record| self.matches(record))
+            .cloned()
+            .collect();
+        if let Some(last) = self.last {
+            matching.drain(..matching.len().saturating_sub(last));
+        }
+        matching
+    }
+
+    /// Renders the matching records, oldest first
+    ///
+    /// `SinkFormat::Styled` renders every record as a single console line, the way
+    /// `make_log!` does, boxes included. The other formats render the lines written
+    /// to log files.
+    pub fn export(&self, format: SinkFormat) -> String {
+        self.records()
+            .into_iter()
+            .map(|record| match format {
+                SinkFormat::Styled => format.format(&record.boxed(false)),
+                _ => format.format(&record),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs, UdpSocket},
+    sync::Mutex,
+};
+
+#[cfg(unix)]
+use std::{os::unix::net::UnixDatagram, path::Path};
+
+use crate::{get_timezone, logger::LogLevel, record::LogRecord};
+
+use super::{Filtered, LevelFilter, Sink};
+
+/// The structured data ID tlogger uses for the title and fields, under the
+/// private enterprise number reserved for documentation
+const SD_ID: &str = "tlogger@32473";
+
+/// The layout of syslog messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyslogFormat {
+    /// `<PRI>1 TIMESTAMP HOSTNAME APP-NAME PROCID MSGID [SD] MSG`, with the title as
+    /// MSGID and the title and fields as structured data
+    #[default]
+    Rfc5424,
+    /// `<PRI>Mmm dd hh:mm:ss HOSTNAME TAG[PID]: MSG`, for older syslog daemons,
+    /// with the title and fields in the message
+    Rfc3164,
+}
+
+/// The syslog facility messages are sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Facility {
+    Kern = 0,
+    #[default]
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    Authpriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+enum Transport {
+    #[cfg(unix)]
+    Unix(UnixDatagram),
+    Udp(UdpSocket),
+    /// Connected on first use, and again after a failed write
+    Tcp {
+        addr: SocketAddr,
+        stream: Mutex<Option<TcpStream>>,
+    },
+}
+
+/// Sends records to a syslog daemon, over a Unix datagram socket, UDP or TCP
+///
+/// Levels map to syslog severities, see `LogLevel::syslog_severity`. Messages sent
+/// over TCP are framed with their length in octets, as described in RFC 6587, in
+/// both formats so messages spanning lines arrive whole.
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// // The local syslog daemon
+/// add_sink(SyslogSink::local().unwrap().with_facility(Facility::Daemon));
+///
+/// // A remote collector, warnings and errors only
+/// add_sink(
+///     SyslogSink::tcp("logs.internal:601")
+///         .unwrap()
+///         .with_app_name("my-app")
+///         .with_min_level(LogLevel::Warn),
+/// );
+/// ```
+pub struct SyslogSink {
+    transport: Transport,
+    format: SyslogFormat,
+    facility: Facility,
+    hostname: String,
+    app_name: String,
+    pid: u32,
+    filter: LevelFilter,
+}
+
+impl SyslogSink {
+    fn new(transport: Transport) -> Self {
+        Self {
+            transport,
+            format: SyslogFormat::default(),
+            facility: Facility::default(),
+            hostname: hostname().unwrap_or_else(|| "-".to_string()),
+            app_name: app_name(),
+            pid: std::process::id(),
+            filter: LevelFilter::default(),
+        }
+    }
+
+    /// Sends to the local syslog daemon at `/dev/log`
+    #[cfg(unix)]
+    pub fn local() -> io::Result<Self> {
+        Self::unix("/dev/log")
+    }
+
+    /// Sends to a syslog daemon listening on a Unix datagram socket
+    #[cfg(unix)]
+    pub fn unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self::new(Transport::Unix(socket)))
+    }
+
+    /// Sends a datagram per record to a syslog daemon
+    pub fn udp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addr = resolve(addr)?;
+        let local: SocketAddr = match addr {
+            SocketAddr::V4(_) => ([0, 0, 0, 0], 0).into(),
+            SocketAddr::V6(_) => ([0u16; 8], 0).into(),
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        Ok(Self::new(Transport::Udp(socket)))
+    }
+
+    /// Streams records to a syslog daemon, reconnecting when the connection is lost
+    pub fn tcp<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        let addr = resolve(addr)?;
+        let stream = TcpStream::connect(addr)?;
+        Ok(Self::new(Transport::Tcp {
+            addr,
+            stream: Mutex::new(Some(stream)),
+        }))
+    }
+
+    pub fn with_format(mut self, format: SyslogFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_facility(mut self, facility: Facility) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// The APP-NAME or TAG of the messages, the name of the executable by default
+    pub fn with_app_name<S: Into<String>>(mut self, app_name: S) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// The HOSTNAME of the messages, the name of this host by default
+    pub fn with_hostname<S: Into<String>>(mut self, hostname: S) -> Self {
+        self.hostname = hostname.into();
+        self
+    }
+
+    /// Renders a record as a syslog message, without framing
+    pub fn format(&self, record: &LogRecord) -> String {
+        let priority = self.facility as u8 * 8 + record.level.syslog_severity();
+        let timestamp = get_timezone().convert(record.timestamp);
+        let message = record.plain_message();
+
+        match self.format {
+            SyslogFormat::Rfc5424 => {
+                let mut structured_data =
+                    format!("[{} title=\"{}\"", SD_ID, sd_escape(&record.title));
+                for (key, value) in &record.fields {
+                    structured_data.push_str(&format!(
+                        " {}=\"{}\"",
+                        sd_name(key),
+                        sd_escape(value)
+                    ));
+                }
+                structured_data.push(']');
+
+                format!(
+                    "<{}>1 {} {} {} {} {} {} {}",
+                    priority,
+                    timestamp.format("%Y-%m-%dT%H:%M:%S%.6f%:z"),
+                    header_field(&self.hostname, 255),
+                    header_field(&self.app_name, 48),
+                    self.pid,
+                    header_field(&record.title, 32),
+                    structured_data,
+                    message
+                )
+            }
+            SyslogFormat::Rfc3164 => {
+                let mut line = format!(
+                    "<{}>{} {} {}[{}]: {}: {}",
+                    priority,
+                    timestamp.format("%b %e %H:%M:%S"),
+                    header_field(&self.hostname, 255),
+                    header_field(&self.app_name, 32),
+                    self.pid,
+                    record.title,
+                    message
+                );
+                if !record.fields.is_empty() {
+                    line.push(' ');
+                    line.push_str(&crate::fields::format_fields(&record.fields));
+                }
+                line
+            }
+        }
+    }
+}
+
+/// Writes a framed message to the TCP stream, connecting first if needed
+fn send_tcp(addr: &SocketAddr, stream: &mut Option<TcpStream>, frame: &[u8]) -> io::Result<()> {
+    let connected = match stream {
+        Some(connected) => connected,
+        None => stream.insert(TcpStream::connect(addr)?),
+    };
+    let result = connected.write_all(frame);
+    if result.is_err() {
+        *stream = None;
+    }
+    result
+}
+
+impl Filtered for SyslogSink {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.filter
+    }
+}
+
+impl Sink for SyslogSink {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.filter.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let message = self.format(record);
+        match &self.transport {
+            #[cfg(unix)]
+            Transport::Unix(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Transport::Udp(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Transport::Tcp { addr, stream } => {
+                // Octet counting, as newline framing would split multi-line messages
+                let frame = format!("{} {}", message.len(), message);
+                let mut stream = stream.lock().unwrap_or_else(|e| e.into_inner());
+                // A connection the daemon closed only shows on the next write, so retry once
+                send_tcp(addr, &mut stream, frame.as_bytes())
+                    .or_else(|_| send_tcp(addr, &mut stream, frame.as_bytes()))
+            }
+        }
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match &self.transport {
+            Transport::Tcp { stream, .. } => {
+                match stream.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+                    Some(stream) => stream.flush(),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+fn resolve<A: ToSocketAddrs>(addr: A) -> io::Result<SocketAddr> {
+    addr.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the syslog address did not resolve",
+        )
+    })
+}
+
+/// A header field as syslog allows it, printable ASCII without spaces, `-` if empty
+fn header_field(value: &str, max_len: usize) -> String {
+    let field: String = value
+        .chars()
+        .map(|c| if c.is_ascii_graphic() { c } else { '_' })
+        .take(max_len)
+        .collect();
+    if field.is_empty() {
+        "-".to_string()
+    } else {
+        field
+    }
+}
+
+/// A structured data parameter name, which additionally can't contain `=`, `]` or `"`
+fn sd_name(key: &str) -> String {
+    header_field(key, 32).replace(['=', ']', '"'], "_")
+}
+
+/// Escapes a structured data parameter value
+fn sd_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '"' | '\\' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The name of the running executable
+pub(super) fn app_name() -> String {
+    std::env::current_exe()
+        .ok()
+        .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "tlogger".to_string())
+}
+
+#[cfg(unix)]
+fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) } != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(not(unix))]
+fn hostname() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
@@ -0,0 +1,74 @@
+use std::{
+    io::{self, Write},
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{logger::LogLevel, record::LogRecord};
+
+use super::{Filtered, Formatted, LevelFilter, Output, Sink, SinkFormat};
+
+/// Writes rendered records to any `Write` target, such as a socket or pipe
+///
+/// Each record is written with a single `write_all`, under a lock.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+///
+/// let file = std::fs::File::create(std::env::temp_dir().join("tlogger-errors.jsonl")).unwrap();
+/// add_sink(
+///     WriterSink::new(file)
+///         .with_levels([LogLevel::Error])
+///         .with_format(SinkFormat::Json),
+/// );
+/// ```
+pub struct WriterSink<W: Write + Send> {
+    writer: Mutex<W>,
+    output: Output,
+}
+
+impl<W: Write + Send> WriterSink<W> {
+    /// Writes records of all levels as plain text lines
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Mutex::new(writer),
+            output: Output::new(SinkFormat::Text),
+        }
+    }
+
+    /// Returns the writer, for example to read back what was written to a buffer
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, W> {
+        self.writer.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<W: Write + Send> Filtered for WriterSink<W> {
+    fn filter_mut(&mut self) -> &mut LevelFilter {
+        &mut self.output.filter
+    }
+}
+
+impl<W: Write + Send> Formatted for WriterSink<W> {
+    fn format_mut(&mut self) -> &mut SinkFormat {
+        &mut self.output.format
+    }
+}
+
+impl<W: Write + Send> Sink for WriterSink<W> {
+    fn enabled(&self, level: &LogLevel) -> bool {
+        self.output.enabled(level)
+    }
+
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let output = self.output.format.format(record);
+        self.lock().write_all(output.as_bytes())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.lock().flush()
+    }
+}
@@ -1,4 +1,7 @@
-use std::sync::OnceLock;
+use std::{
+    cell::RefCell,
+    sync::{Arc, LazyLock, RwLock},
+};
 
 /// Colors using ANSI escape codes
 pub struct Colors {
@@ -13,6 +16,8 @@ pub struct Colors {
     pub success_text: &'static str,
     pub debug: &'static str,
     pub debug_text: &'static str,
+    pub trace: &'static str,
+    pub trace_text: &'static str,
 
     // Text styling
     pub dim: &'static str,
@@ -30,6 +35,7 @@ pub struct Symbols {
     pub error: &'static str,
     pub success: &'static str,
     pub debug: &'static str,
+    pub trace: &'static str,
     pub separator: &'static str,
     pub bullet: &'static str,
 }
@@ -58,6 +64,8 @@ impl Default for Colors {
             success_text: "\x1b[32m", // Dark Green
             debug: "\x1b[95m",        // Bright Magenta
             debug_text: "\x1b[35m",   // Dark Magenta
+            trace: "\x1b[94m",        // Bright Blue
+            trace_text: "\x1b[34m",   // Dark Blue
 
             // Text styling
             dim: "\x1b[2m",           // Dimmed
@@ -78,6 +86,7 @@ impl Default for Symbols {
             error: "✖",
             success: "✔",
             debug: "⁂",
+            trace: "⋯",
             separator: "│",
             bullet: "•",
         }
@@ -97,6 +106,41 @@ impl Default for Borders {
     }
 }
 
-pub static COLORS: OnceLock<Colors> = OnceLock::new();
-pub static SYMBOLS: OnceLock<Symbols> = OnceLock::new();
-pub static BORDERS: OnceLock<Borders> = OnceLock::new();
+pub static COLORS: LazyLock<RwLock<Arc<Colors>>> = LazyLock::new(RwLock::default);
+pub static SYMBOLS: LazyLock<RwLock<Arc<Symbols>>> = LazyLock::new(RwLock::default);
+pub static BORDERS: LazyLock<RwLock<Arc<Borders>>> = LazyLock::new(RwLock::default);
+
+/// Colors, symbols and borders used together, as kept by a `TLogger`
+#[derive(Clone, Default)]
+pub(crate) struct Theme {
+    pub(crate) colors: Arc<Colors>,
+    pub(crate) symbols: Arc<Symbols>,
+    pub(crate) borders: Arc<Borders>,
+}
+
+thread_local! {
+    /// The theme of the `TLogger` rendering a record on this thread, if any
+    static SCOPED_THEME: RefCell<Option<Theme>> = const { RefCell::new(None) };
+}
+
+/// Puts the previous theme back once a scope ends, even if rendering panicked
+struct ScopeGuard(Option<Theme>);
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPED_THEME.set(self.0.take());
+    }
+}
+
+impl Theme {
+    /// Runs `f` with this theme used instead of the global one on the current thread
+    pub(crate) fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        let _guard = ScopeGuard(SCOPED_THEME.replace(Some(self.clone())));
+        f()
+    }
+
+    /// The part of the scoped theme picked by `part`, if a theme is scoped on this thread
+    pub(crate) fn scoped<T>(part: impl FnOnce(&Theme) -> &Arc<T>) -> Option<Arc<T>> {
+        SCOPED_THEME.with_borrow(|theme| theme.as_ref().map(|theme| Arc::clone(part(theme))))
+    }
+}
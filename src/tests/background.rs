@@ -0,0 +1,106 @@
+use std::{fs, process::Command};
+
+use crate::{
+    logger::{LogInterval, LogLevel, Logger, OverflowPolicy},
+    opts::{add_sink, clear_sinks},
+    record::LogRecord,
+};
+
+fn read_lines(dir: &std::path::Path) -> Vec<String> {
+    let mut lines = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let contents = fs::read_to_string(entry.unwrap().path()).unwrap();
+        lines.extend(contents.lines().map(str::to_string));
+    }
+    lines
+}
+
+#[test]
+fn writes_queued_records_on_flush_and_drop() {
+    let dir = std::env::temp_dir().join(format!("tlogger-async-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_async(4, OverflowPolicy::Block).unwrap();
+
+    for i in 0..100 {
+        let record = LogRecord::new(LogLevel::Info, "Queue", format!("Record {}", i));
+        logger.log(&record).unwrap();
+    }
+    logger.flush();
+    let flushed = read_lines(&dir);
+
+    logger
+        .log(&LogRecord::new(LogLevel::Info, "Server", "Stopping"))
+        .unwrap();
+    drop(logger);
+    let dropped = read_lines(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(flushed.len(), 100);
+    for (i, line) in flushed.iter().enumerate() {
+        assert!(line.ends_with(&format!("│ Queue Record {}", i)));
+    }
+    assert_eq!(dropped.len(), 101);
+    assert!(dropped[100].ends_with("│ Server Stopping"));
+}
+
+#[test]
+fn reports_dropped_records() {
+    let dir = std::env::temp_dir().join(format!("tlogger-async-drop-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_async(1, OverflowPolicy::DropOldest).unwrap();
+
+    for i in 0..1000 {
+        let record = LogRecord::new(LogLevel::Info, "Queue", format!("Record {}", i));
+        logger.log(&record).unwrap();
+    }
+    drop(logger);
+    let lines = read_lines(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    let dropped: u64 = lines
+        .iter()
+        .filter_map(|line| line.split("│ Logger Dropped ").nth(1))
+        .map(|rest| rest.split(' ').next().unwrap().parse::<u64>().unwrap())
+        .sum();
+    let written = lines.iter().filter(|line| line.contains("│ Queue")).count();
+
+    assert_eq!(written as u64 + dropped, 1000);
+    // The newest record is never the one dropped
+    assert!(lines.last().unwrap().ends_with("│ Queue Record 999"));
+}
+
+#[test]
+fn writes_queued_records_of_sinks_at_exit() {
+    // Run again in a child process, which logs and exits with records still queued
+    if let Ok(dir) = std::env::var("TLOGGER_EXIT_DIR") {
+        let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+        logger.set_async(20_000, OverflowPolicy::Block).unwrap();
+        clear_sinks();
+        add_sink(logger);
+        for i in 0..20_000 {
+            crate::log_record(&LogRecord::new(
+                LogLevel::Info,
+                "Queue",
+                format!("Record {}", i),
+            ));
+        }
+        std::process::exit(0);
+    }
+
+    let dir = std::env::temp_dir().join(format!("tlogger-async-exit-{}", std::process::id()));
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "tests::background::writes_queued_records_of_sinks_at_exit",
+            "--exact",
+            "--test-threads=1",
+        ])
+        .env("TLOGGER_EXIT_DIR", &dir)
+        .output()
+        .unwrap();
+    let lines = read_lines(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    let written = lines.iter().filter(|line| line.contains("│ Queue")).count();
+    assert_eq!(written, 20_000);
+}
@@ -0,0 +1,117 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::{
+    assert_logged, assert_not_logged,
+    capture::{capture_logs, captured, console_quiet, LogCapture},
+    error, info,
+    instance::TLogger,
+    logger::LogLevel,
+    record::LogRecord,
+    sinks::Filtered,
+    warn,
+};
+
+#[test]
+fn captures_records_logged_on_this_thread() {
+    let logger = TLogger::new().without_sinks();
+    let capture = LogCapture::start();
+
+    warn!(logger => "Memory", "Usage at **{}%**", 85);
+    std::thread::spawn(move || error!(logger => "Database", "Connection failed"))
+        .join()
+        .unwrap();
+
+    assert_logged!(Warn, title = "Memory", contains = "85%");
+    assert_logged!(Warn, message = "Usage at 85%");
+    assert_not_logged!(Error);
+    assert_eq!(capture.records().len(), 1);
+
+    capture.clear();
+    assert_not_logged!(Warn);
+}
+
+#[test]
+fn records_are_captured_before_level_filters() {
+    let logger = TLogger::new()
+        .without_sinks()
+        .with_min_level(LogLevel::Warn);
+
+    let records = capture_logs(|| {
+        info!(logger => "Server", "Starting");
+        crate::debug!("Server", "Listening");
+    });
+
+    assert_logged!(records => Info, title = "Server", message = "Starting");
+    assert_logged!(records => Debug, title = "Server", message = "Listening");
+}
+
+#[test]
+fn nested_captures_see_what_is_logged_while_they_are_alive() {
+    let logger = TLogger::new().without_sinks();
+    let outer = LogCapture::start();
+    info!(logger => "Server", "Starting");
+
+    let records = capture_logs(|| {
+        warn!(logger => "Memory", "Usage at {}%", 85);
+        assert_not_logged!(Info, title = "Server");
+    });
+    assert_logged!(records => Warn, title = "Memory");
+    assert_not_logged!(records => Info);
+
+    assert_logged!(Info, title = "Server");
+    assert_logged!(Warn, title = "Memory");
+    assert_eq!(outer.records().len(), 2);
+    drop(outer);
+
+    let logged = catch_unwind(|| captured());
+    assert!(logged.is_err());
+}
+
+#[test]
+fn quiet_captures_suppress_the_console_until_dropped() {
+    let capture = LogCapture::start();
+    assert!(!console_quiet());
+
+    let quiet = LogCapture::start().quiet().quiet();
+    assert!(console_quiet());
+    let records = capture_logs(|| assert!(console_quiet()));
+    assert!(records.is_empty());
+    assert!(console_quiet());
+
+    drop(quiet);
+    assert!(!console_quiet());
+    drop(capture);
+}
+
+#[test]
+fn failed_assertions_list_the_captured_records() {
+    let records = vec![
+        LogRecord::new(LogLevel::Warn, "Memory", "Usage at **85%**"),
+        LogRecord::new(LogLevel::Info, "Server", "Starting"),
+    ];
+
+    let failure = catch_unwind(AssertUnwindSafe(|| {
+        assert_logged!(records => Error, title = "Database", contains = "failed");
+    }))
+    .unwrap_err();
+    let message = failure.downcast_ref::<String>().unwrap();
+    assert!(message.starts_with(
+        "expected a record at level error titled \"Database\" containing \"failed\" to be logged"
+    ));
+    assert!(message.contains("\n  warn Memory: Usage at 85%"));
+    assert!(message.contains("\n  info Server: Starting"));
+
+    let failure = catch_unwind(AssertUnwindSafe(|| {
+        assert_not_logged!(records => Warn, contains = "85%");
+    }))
+    .unwrap_err();
+    let message = failure.downcast_ref::<String>().unwrap();
+    assert!(
+        message.starts_with("expected a record at level warn containing \"85%\" not to be logged")
+    );
+
+    let empty: Vec<LogRecord> = Vec::new();
+    let failure = catch_unwind(|| assert_logged!(empty => Info)).unwrap_err();
+    let message = failure.downcast_ref::<String>().unwrap();
+    assert!(message.ends_with("but nothing was logged"));
+}
@@ -0,0 +1,142 @@
+use crate::{fields::format_fields, prelude::*};
+
+#[test]
+fn log_parts_collect_fields() {
+    let id = 7;
+    let elapsed = 12.5;
+
+    let (message, fields) = crate::__log_parts!("User connected");
+    assert_eq!(message, "User connected");
+    assert!(fields.is_empty());
+
+    let (message, fields) = crate::__log_parts!("User {} connected", id == 7,);
+    assert_eq!(message, "User true connected");
+    assert!(fields.is_empty());
+
+    let (message, fields) = crate::__log_parts!("User connected"; user = id, ms = elapsed);
+    assert_eq!(message, "User connected");
+    assert_eq!(
+        fields,
+        [("user", "7".to_string()), ("ms", "12.5".to_string())]
+    );
+
+    let (message, fields) =
+        crate::__log_parts!("{} {user} in {ms}ms", "Login", user = id, ms = elapsed; ok = true);
+    assert_eq!(message, "Login 7 in 12.5ms");
+    assert_eq!(
+        fields,
+        [
+            ("user", "7".to_string()),
+            ("ms", "12.5".to_string()),
+            ("ok", "true".to_string())
+        ]
+    );
+
+    // Values without a Display implementation fall back to Debug
+    let (_, fields) = crate::__log_parts!("Queue"; items = vec![1, 2]);
+    assert_eq!(fields, [("items", "[1, 2]".to_string())]);
+}
+
+#[test]
+fn inline_captures_become_fields() {
+    let user = "Alice";
+    let ms = 12.5;
+    let width = 8;
+
+    let (message, fields) = crate::__log_parts!("{user} in");
+    assert_eq!(message, "Alice in");
+    assert_eq!(fields, [("user", "Alice".to_string())]);
+
+    // Escaped braces, positional arguments and widths are not fields
+    let (message, fields) = crate::__log_parts!("{{user}} {} {0} {user:>width$} in {ms:.0}ms, {user}", "Login"; ok = true);
+    assert_eq!(message, "{user} Login Login    Alice in 12ms, Alice");
+    assert_eq!(
+        fields,
+        [
+            ("user", "Alice".to_string()),
+            ("ms", "12.5".to_string()),
+            ("ok", "true".to_string())
+        ]
+    );
+
+    // Named arguments take precedence over a variable of the same name
+    let (message, fields) = crate::__log_parts!("{user} in {ms}ms", user = 7);
+    assert_eq!(message, "7 in 12.5ms");
+    assert_eq!(
+        fields,
+        [("user", "7".to_string()), ("ms", "12.5".to_string())]
+    );
+
+    let records = capture_logs(|| info!("Login", r#"{user} logged in"#));
+    assert_eq!(records[0].message, "Alice logged in");
+    assert_eq!(records[0].fields, [("user", "Alice".to_string())]);
+}
+
+#[test]
+fn fields_are_quoted_when_needed() {
+    let fields = [
+        ("user", "Alice".to_string()),
+        ("reason", "bad password".to_string()),
+        ("query", "a=\"b\"".to_string()),
+        ("empty", String::new()),
+    ];
+
+    assert_eq!(
+        format_fields(&fields),
+        r#"user=Alice reason="bad password" query="a=\"b\"" empty="""#
+    );
+}
+
+#[test]
+fn macros_accept_fields() {
+    let id = "Alice";
+    let elapsed = 42;
+
+    let records = capture_logs(|| {
+        info!("Login", "User connected"; user = id, ms = elapsed);
+        warn!("Memory", "Usage at {usage}%", usage = 85);
+        debug!("Queue", "Items in queue: {}", 3; worker = 1);
+        success_box!("Login", "User {user} connected", user = id; ms = elapsed);
+        error_box!("Database", "Connection failed"; host = "db.internal", retry_in = "5 s");
+    });
+
+    let logged: Vec<_> = records
+        .iter()
+        .map(|record| {
+            (
+                record.title.as_str(),
+                record.message.as_str(),
+                &record.fields[..],
+            )
+        })
+        .collect();
+    assert_eq!(
+        logged,
+        [
+            (
+                "Login",
+                "User connected",
+                &[("user", "Alice".to_string()), ("ms", "42".to_string())][..]
+            ),
+            ("Memory", "Usage at 85%", &[("usage", "85".to_string())][..]),
+            (
+                "Queue",
+                "Items in queue: 3",
+                &[("worker", "1".to_string())][..]
+            ),
+            (
+                "Login",
+                "User Alice connected",
+                &[("user", "Alice".to_string()), ("ms", "42".to_string())][..]
+            ),
+            (
+                "Database",
+                "Connection failed",
+                &[
+                    ("host", "db.internal".to_string()),
+                    ("retry_in", "5 s".to_string())
+                ][..]
+            ),
+        ]
+    );
+}
@@ -0,0 +1,170 @@
+use std::{
+    fs,
+    path::Path,
+    process::{Command, Output},
+};
+
+use crate::{
+    debug, error,
+    filter::Directives,
+    info,
+    logger::{read_log_file, LogInterval, LogLevel, Logger},
+    opts::{add_sink, flush_sinks, init_logger, reset, set_debug},
+    record::{Callsite, LogRecord},
+    strip_ansi_codes, warn,
+};
+
+fn record(level: LogLevel, module_path: &'static str, title: &str) -> LogRecord {
+    LogRecord::new(level, title, "message").with_callsite(Callsite::new(
+        module_path,
+        "src/lib.rs",
+        1,
+    ))
+}
+
+#[test]
+fn default_level_and_module_paths() {
+    let directives: Directives = "warn,my_crate::db=debug,my_crate::db::pool=error"
+        .parse()
+        .unwrap();
+
+    assert!(!directives.enabled(&record(LogLevel::Info, "my_crate", "Server")));
+    assert!(directives.enabled(&record(LogLevel::Warn, "my_crate", "Server")));
+    assert!(directives.enabled(&record(LogLevel::Debug, "my_crate::db", "Query")));
+    assert!(directives.enabled(&record(
+        LogLevel::Debug,
+        "my_crate::db::migrations",
+        "Query"
+    )));
+    assert!(!directives.enabled(&record(LogLevel::Debug, "my_crate::dbx", "Query")));
+    assert!(!directives.enabled(&record(LogLevel::Warn, "my_crate::db::pool", "Pool")));
+
+    // Records without a callsite only match the default
+    assert!(!directives.enabled(&LogRecord::new(LogLevel::Info, "Server", "message")));
+}
+
+#[test]
+fn titles_take_precedence() {
+    let directives: Directives = "info,my_crate::db=off,title:Database=trace"
+        .parse()
+        .unwrap();
+
+    assert!(directives.enabled(&record(LogLevel::Debug, "my_crate::db", "Database")));
+    assert!(!directives.enabled(&record(LogLevel::Error, "my_crate::db", "Query")));
+    assert!(!directives.enabled(&record(LogLevel::Debug, "my_crate", "Server")));
+    assert!(directives.enabled(&record(LogLevel::Success, "my_crate", "Server")));
+}
+
+#[test]
+fn missing_default_lets_everything_else_through() {
+    let directives: Directives = "my_crate::db=error,title:Metrics".parse().unwrap();
+
+    assert!(directives.enabled(&record(LogLevel::Debug, "my_crate", "Server")));
+    assert!(directives.enabled(&record(LogLevel::Debug, "my_crate", "Metrics")));
+    assert!(!directives.enabled(&record(LogLevel::Warn, "my_crate::db", "Query")));
+    assert!(Directives::default().enabled(&record(LogLevel::Debug, "my_crate", "Server")));
+}
+
+#[test]
+fn matching_directives_replace_the_global_levels() {
+    let directives: Directives = "warn,title:Database=trace".parse().unwrap();
+
+    assert!(directives.enabled_or(&record(LogLevel::Debug, "my_crate", "Database"), || false));
+    assert!(!directives.enabled_or(&record(LogLevel::Info, "my_crate", "Server"), || true));
+    assert!(directives.may_enable(&LogLevel::Trace));
+
+    let directives: Directives = "title:Metrics=off".parse().unwrap();
+    assert!(!directives.enabled_or(&record(LogLevel::Debug, "my_crate", "Server"), || false));
+    assert!(!directives.may_enable(&LogLevel::Error));
+}
+
+/// Runs the test again in a child process, with `TLOGGER_FILTER_DIR` set to `dir`
+/// and directives turning `Database` up to trace and everything else down to warn
+///
+/// The directives are only read once, so they can't be changed in this process.
+fn run_with_directives(test: &str, dir: &Path) -> Output {
+    _ = fs::remove_dir_all(dir);
+    Command::new(std::env::current_exe().unwrap())
+        .args([
+            &format!("tests::filter::{test}"),
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env("TLOGGER_FILTER_DIR", dir)
+        .env("TLOGGER", "warn,title:Database=trace")
+        .env_remove("TLOGGER_CONSOLE")
+        .env_remove("TLOGGER_FILE")
+        .output()
+        .unwrap()
+}
+
+/// The contents of the log file in `dir`, removing the directory
+fn read_dir_contents(dir: &Path) -> String {
+    let file = fs::read_dir(dir).unwrap().next().unwrap().unwrap().path();
+    let contents = read_log_file(file).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+    contents
+}
+
+#[test]
+fn directives_turn_levels_up_past_set_debug() {
+    // Run again in a child process, as the directives are only read once
+    if let Ok(dir) = std::env::var("TLOGGER_FILTER_DIR") {
+        init_logger(&dir, LogInterval::OneDay).unwrap();
+        set_debug(false);
+        debug!("Database", "Connected to the primary");
+        debug!("Server", "Hidden debug");
+        info!("Server", "Hidden info");
+        warn!("Server", "Shown warning");
+        flush_sinks().unwrap();
+        std::process::exit(0);
+    }
+
+    let dir = std::env::temp_dir().join(format!("tlogger-filter-{}", std::process::id()));
+    let output = run_with_directives("directives_turn_levels_up_past_set_debug", &dir);
+    let contents = read_dir_contents(&dir);
+
+    assert!(output.status.success());
+    for written in [
+        strip_ansi_codes(&String::from_utf8_lossy(&output.stdout)),
+        contents,
+    ] {
+        assert!(written.contains("Connected to the primary"));
+        assert!(written.contains("Shown warning"));
+        assert!(!written.contains("Hidden"));
+    }
+}
+
+#[test]
+fn directives_only_narrow_loggers_added_as_sinks() {
+    if let Ok(dir) = std::env::var("TLOGGER_FILTER_DIR") {
+        let mut errors = Logger::new(&dir, LogInterval::OneDay).unwrap();
+        errors.clear_log_levels();
+        errors.add_log_level(LogLevel::Error);
+        add_sink(errors);
+
+        debug!("Database", "Connected to the primary");
+        warn!("Server", "Hidden warning");
+        error!("Server", "Shown error");
+        info!("Server", "Hidden info");
+        reset();
+        std::process::exit(0);
+    }
+
+    let dir = std::env::temp_dir().join(format!("tlogger-filter-sink-{}", std::process::id()));
+    let output = run_with_directives("directives_only_narrow_loggers_added_as_sinks", &dir);
+    let contents = read_dir_contents(&dir);
+
+    assert!(output.status.success());
+    assert_eq!(contents.lines().count(), 1, "{contents}");
+    assert!(contents.contains("Shown error"));
+}
+
+#[test]
+fn invalid_directives() {
+    assert!("my_crate=loud".parse::<Directives>().is_err());
+    assert!("title:=warn".parse::<Directives>().is_err());
+    assert!("=warn".parse::<Directives>().is_err());
+    assert!("WARN, my_crate = Debug,".parse::<Directives>().is_ok());
+}
@@ -0,0 +1,46 @@
+use chrono::{Local, TimeZone};
+
+use crate::{format::LogFormat, logger::LogLevel, record::LogRecord};
+
+fn record() -> LogRecord {
+    let mut record = LogRecord::new(LogLevel::Warn, "Memory", "Usage at **85%**\n\"high\"")
+        .with_fields(vec![
+            ("usage", "85".to_string()),
+            ("host", "db 1".to_string()),
+        ]);
+    record.timestamp = Local.with_ymd_and_hms(2024, 11, 20, 10, 15, 2).unwrap();
+    record
+}
+
+#[test]
+fn json_lines() {
+    let record = record();
+    let timestamp = record
+        .timestamp
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+
+    assert_eq!(
+        LogFormat::Json.format(&record),
+        format!(
+            "{{\"timestamp\":\"{timestamp}\",\"level\":\"warn\",\"title\":\"Memory\",\
+             \"message\":\"Usage at 85%\\n\\\"high\\\"\",\"fields\":{{\"usage\":\"85\",\"host\":\"db 1\"}}}}\n"
+        )
+    );
+}
+
+#[test]
+fn logfmt() {
+    let mut record = record();
+    record.title = "Memory pool".to_string();
+    let timestamp = record
+        .timestamp
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, false);
+
+    assert_eq!(
+        LogFormat::Logfmt.format(&record),
+        format!(
+            "ts={timestamp} level=warn title=\"Memory pool\" msg=\"Usage at 85%\\n\\\"high\\\"\" \
+             usage=85 host=\"db 1\"\n"
+        )
+    );
+}
@@ -0,0 +1,80 @@
+use std::{fs, io, thread};
+
+use crate::{
+    get_colors, get_text_styling,
+    logger::{read_log_file, FileNaming, LogInterval, LogLevel},
+    opts::{
+        customize::customize_colors, init_logger, reset, set_file_naming, set_log_level,
+        set_max_file_size,
+    },
+    read,
+    record::LogRecord,
+    styling::Colors,
+    text_styling_off, LOGGER,
+};
+
+fn log(level: LogLevel, message: &str) {
+    crate::log_record(&LogRecord::new(level, "GlobalTest", message));
+}
+
+fn contents(dir: &std::path::Path) -> String {
+    let mut contents = String::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        contents.push_str(&read_log_file(entry.unwrap().path()).unwrap());
+    }
+    contents
+}
+
+#[test]
+fn logger_can_be_replaced_and_changed_from_any_thread() {
+    let _globals = super::lock_globals();
+    let dir = std::env::temp_dir().join(format!("tlogger-global-{}", std::process::id()));
+    let first = dir.join("first");
+    let second = dir.join("second");
+    _ = fs::remove_dir_all(&dir);
+
+    init_logger(&first, LogInterval::OneDay).unwrap();
+    log(LogLevel::Info, "To the first logger");
+
+    init_logger(&second, LogInterval::OneDay).unwrap();
+    thread::spawn(|| set_log_level(LogLevel::Warn))
+        .join()
+        .unwrap();
+    log(LogLevel::Info, "Below the level set from another thread");
+    log(LogLevel::Warn, "To the second logger");
+    reset();
+
+    let first = contents(&first);
+    let second = contents(&second);
+    assert!(first.contains("To the first logger"));
+    assert!(!first.contains("To the second logger"));
+    assert!(second.contains("To the second logger"));
+    assert!(!second.contains("Below the level"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn reset_restores_the_defaults() {
+    let _globals = super::lock_globals();
+    let dir = std::env::temp_dir().join(format!("tlogger-reset-{}", std::process::id()));
+
+    init_logger(&dir, LogInterval::OneDay).unwrap();
+    text_styling_off();
+    customize_colors(Colors {
+        info: "[info]",
+        ..Default::default()
+    });
+    reset();
+
+    assert!(read(&LOGGER).is_none());
+    assert!(get_text_styling());
+    assert_eq!(get_colors().info, Colors::default().info);
+
+    // Without a logger setters do nothing, or fail if they return a result
+    set_max_file_size(1024);
+    let result = set_file_naming(FileNaming::default());
+    assert_eq!(result.unwrap_err().kind(), io::ErrorKind::NotFound);
+
+    _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,53 @@
+use crate::{
+    get_symbols,
+    instance::TLogger,
+    logger::LogLevel,
+    opts::add_sink,
+    sinks::{Filtered, Formatted, MemorySink, SinkFormat},
+    styling::Symbols,
+};
+
+#[test]
+fn loggers_keep_their_own_theme_and_levels() {
+    let _globals = super::lock_globals();
+    let global = MemorySink::new();
+    add_sink(global.clone());
+
+    let first = MemorySink::new().with_format(SinkFormat::Styled);
+    let second = MemorySink::new().with_format(SinkFormat::Styled);
+    let first_logger = TLogger::new()
+        .without_sinks()
+        .with_symbols(Symbols {
+            warn: "[first]",
+            ..Default::default()
+        })
+        .with_sink(first.clone());
+    let second_logger = TLogger::new()
+        .without_sinks()
+        .with_symbols(Symbols {
+            warn: "[second]",
+            ..Default::default()
+        })
+        .with_sink(second.clone())
+        .with_min_level(LogLevel::Warn);
+
+    crate::warn!(first_logger => "InstanceTest", "Usage at {}%", 85);
+    crate::warn!(&second_logger => "InstanceTest", "Usage at {usage}%", usage = 90);
+    crate::info!(second_logger => "InstanceTest", "Below the level of the logger");
+    crate::error_box!(second_logger => "InstanceTest", "Connection failed");
+
+    let first = first.lines();
+    let second = second.lines();
+    assert_eq!(first.len(), 1);
+    assert!(first[0].contains("[first]") && first[0].contains("Usage at 85%"));
+    assert_eq!(second.len(), 2);
+    assert!(second[0].contains("[second]") && second[0].contains("usage=90"));
+    assert!(second[1].starts_with(&format!("{}╭", crate::get_colors().error)));
+
+    // Neither the global theme nor the global sinks are touched
+    assert_eq!(get_symbols().warn, Symbols::default().warn);
+    assert!(!global
+        .lines()
+        .iter()
+        .any(|line| line.contains("InstanceTest")));
+}
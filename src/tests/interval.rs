@@ -0,0 +1,155 @@
+use std::time::Duration;
+
+use chrono::{FixedOffset, Local, TimeZone, Utc};
+
+use crate::{
+    format::LogFormat,
+    logger::{FileNaming, LogInterval, LogLevel},
+    record::LogRecord,
+    timezone::Timezone,
+};
+
+fn timestamp(interval: LogInterval, hour: u32, minute: u32, second: u32) -> String {
+    let now = Local
+        .with_ymd_and_hms(2024, 11, 20, hour, minute, second)
+        .unwrap();
+    interval.get_file_timestamp(now.fixed_offset())
+}
+
+#[test]
+fn sub_day_intervals_align_to_midnight() {
+    assert_eq!(
+        timestamp(LogInterval::OneHour, 10, 17, 42),
+        "2024-11-20-10h-11h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::OneHour, 23, 59, 59),
+        "2024-11-20-23h-00h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::ThreeHour, 10, 17, 42),
+        "2024-11-20-09h-12h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::TwelveHour, 13, 0, 0),
+        "2024-11-20-12h-00h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::OneDay, 10, 17, 42),
+        "2024-11-20-00h-24h"
+    );
+
+    assert_eq!(
+        timestamp(LogInterval::OneMinute, 10, 17, 42),
+        "2024-11-20-10h17-10h18"
+    );
+    assert_eq!(
+        timestamp(LogInterval::FiveMinute, 10, 17, 42),
+        "2024-11-20-10h15-10h20"
+    );
+    assert_eq!(
+        timestamp(LogInterval::FifteenMinute, 10, 17, 42),
+        "2024-11-20-10h15-10h30"
+    );
+    assert_eq!(
+        timestamp(LogInterval::ThirtyMinute, 23, 45, 0),
+        "2024-11-20-23h30-00h00"
+    );
+}
+
+#[test]
+fn uneven_intervals_are_cut_short_at_midnight() {
+    assert_eq!(
+        timestamp(LogInterval::NineHour, 8, 0, 0),
+        "2024-11-20-00h-09h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::NineHour, 17, 0, 0),
+        "2024-11-20-09h-18h"
+    );
+    assert_eq!(
+        timestamp(LogInterval::NineHour, 23, 0, 0),
+        "2024-11-20-18h-00h"
+    );
+
+    let seven_minutes = LogInterval::Every(Duration::from_secs(7 * 60));
+    assert_eq!(
+        timestamp(seven_minutes, 23, 58, 0),
+        "2024-11-20-23h55-00h00"
+    );
+}
+
+#[test]
+fn every_uses_the_precision_it_needs() {
+    let ninety_minutes = LogInterval::Every(Duration::from_secs(90 * 60));
+    assert_eq!(
+        timestamp(ninety_minutes, 10, 17, 42),
+        "2024-11-20-09h00-10h30"
+    );
+
+    let two_hours = LogInterval::Every(Duration::from_secs(2 * 60 * 60));
+    assert_eq!(timestamp(two_hours, 10, 17, 42), "2024-11-20-10h-12h");
+
+    let thirty_seconds = LogInterval::Every(Duration::from_secs(30));
+    assert_eq!(
+        timestamp(thirty_seconds, 10, 17, 42),
+        "2024-11-20-10h17m30s-10h18m00s"
+    );
+
+    let three_days = LogInterval::Every(Duration::from_secs(3 * 24 * 60 * 60));
+    assert_eq!(timestamp(three_days, 10, 17, 42), "2024-11-19-3d");
+}
+
+#[test]
+fn calendar_intervals() {
+    assert_eq!(timestamp(LogInterval::Weekly, 10, 17, 42), "2024-W47");
+    assert_eq!(timestamp(LogInterval::Monthly, 10, 17, 42), "2024-11");
+}
+
+#[test]
+fn intervals_follow_the_timezone() {
+    let tokyo = Timezone::Fixed(FixedOffset::east_opt(9 * 3600).unwrap());
+    let now = tokyo.convert(
+        Utc.with_ymd_and_hms(2024, 11, 20, 20, 15, 0)
+            .unwrap()
+            .into(),
+    );
+
+    assert_eq!(
+        LogInterval::OneHour.get_file_timestamp(now),
+        "2024-11-21-05h-06h"
+    );
+    assert_eq!(
+        FileNaming::default().stem(&LogInterval::OneHour, tokyo, now),
+        "2024-11-21-05h-06h"
+    );
+}
+
+#[test]
+fn offset_is_appended_when_it_changed_during_the_interval() {
+    // The local offset at the start of the interval is never UTC+14 in practice,
+    // like the repeated hour after the clock is turned back
+    let now = FixedOffset::east_opt(14 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 11, 20, 10, 15, 0)
+        .unwrap();
+
+    assert_eq!(
+        FileNaming::default().stem(&LogInterval::OneHour, Timezone::Local, now),
+        "2024-11-20-10h-11h+1400"
+    );
+}
+
+#[test]
+fn file_timestamps_include_the_offset() {
+    let mut record = LogRecord::new(LogLevel::Info, "Server", "Starting");
+    record.timestamp = Utc.with_ymd_and_hms(2024, 11, 20, 9, 15, 2).unwrap().into();
+
+    assert_eq!(
+        LogFormat::Text.format_with_timezone(&record, Timezone::Utc),
+        "INFO    09:15:02.000+00:00 │ Server Starting\n"
+    );
+    assert!(LogFormat::Json
+        .format_with_timezone(&record, Timezone::Utc)
+        .contains("\"timestamp\":\"2024-11-20T09:15:02.000+00:00\""));
+}
@@ -0,0 +1,152 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    os::{
+        fd::{FromRawFd, RawFd},
+        unix::net::UnixDatagram,
+    },
+    path::PathBuf,
+};
+
+use crate::{
+    logger::LogLevel,
+    record::{Callsite, LogRecord},
+    sinks::{Filtered, JournaldSink, Sink},
+};
+
+fn socket(name: &str) -> (PathBuf, UnixDatagram) {
+    let path = std::env::temp_dir().join(format!(
+        "tlogger-journald-{}-{}.sock",
+        name,
+        std::process::id()
+    ));
+    _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path).unwrap();
+    (path, socket)
+}
+
+/// Parses the native journal protocol back into its fields
+fn parse(mut entry: &[u8]) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    while !entry.is_empty() {
+        let end = entry.iter().position(|&b| b == b'=' || b == b'\n').unwrap();
+        let name = String::from_utf8(entry[..end].to_vec()).unwrap();
+        let value;
+        if entry[end] == b'=' {
+            let len = entry[end + 1..].iter().position(|&b| b == b'\n').unwrap();
+            value = &entry[end + 1..end + 1 + len];
+            entry = &entry[end + 1 + len + 1..];
+        } else {
+            let len = u64::from_le_bytes(entry[end + 1..end + 9].try_into().unwrap()) as usize;
+            value = &entry[end + 9..end + 9 + len];
+            assert_eq!(entry[end + 9 + len], b'\n');
+            entry = &entry[end + 9 + len + 1..];
+        }
+        fields.push((name, String::from_utf8(value.to_vec()).unwrap()));
+    }
+    fields
+}
+
+fn field<'a>(fields: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(field, _)| field == name)
+        .map(|(_, value)| value.as_str())
+}
+
+#[test]
+fn records_become_journal_entries() {
+    let (path, listener) = socket("entries");
+    let sink = JournaldSink::at(&path)
+        .unwrap()
+        .with_identifier("my-app")
+        .with_min_level(LogLevel::Info);
+    assert!(!sink.enabled(&LogLevel::Debug));
+
+    let record = LogRecord::new(LogLevel::Success, "Login", "User **Alice**\nconnected")
+        .with_fields(vec![
+            ("user", "Alice".to_string()),
+            ("_ms", "42".to_string()),
+            ("message", "Overridden".to_string()),
+            ("priority", "0".to_string()),
+            ("syslog_identifier", "other-app".to_string()),
+            ("2fa", "true".to_string()),
+        ])
+        .with_callsite(Callsite::new("my_app::auth", "src/auth.rs", 12));
+    sink.log(&record).unwrap();
+
+    let mut buf = [0; 4096];
+    let len = listener.recv(&mut buf).unwrap();
+    let fields = parse(&buf[..len]);
+
+    assert_eq!(field(&fields, "MESSAGE"), Some("User Alice\nconnected"));
+    assert_eq!(field(&fields, "PRIORITY"), Some("5"));
+    assert_eq!(field(&fields, "SYSLOG_IDENTIFIER"), Some("my-app"));
+    assert_eq!(field(&fields, "TLOGGER_TITLE"), Some("Login"));
+    assert_eq!(field(&fields, "TLOGGER_LEVEL"), Some("success"));
+    assert_eq!(field(&fields, "CODE_FILE"), Some("src/auth.rs"));
+    assert_eq!(field(&fields, "CODE_LINE"), Some("12"));
+    assert_eq!(field(&fields, "TLOGGER_F_USER"), Some("Alice"));
+    assert_eq!(field(&fields, "TLOGGER_F_MS"), Some("42"));
+    assert_eq!(field(&fields, "TLOGGER_F_2FA"), Some("true"));
+
+    // Fields named like the fields of the entry don't replace them
+    let count = |name| fields.iter().filter(|(field, _)| field == name).count();
+    assert_eq!(count("MESSAGE"), 1);
+    assert_eq!(count("PRIORITY"), 1);
+    assert_eq!(count("SYSLOG_IDENTIFIER"), 1);
+    assert_eq!(field(&fields, "TLOGGER_F_MESSAGE"), Some("Overridden"));
+    assert_eq!(field(&fields, "TLOGGER_F_PRIORITY"), Some("0"));
+    assert_eq!(
+        field(&fields, "TLOGGER_F_SYSLOG_IDENTIFIER"),
+        Some("other-app")
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn large_records_are_passed_in_a_memfd() {
+    let (path, listener) = socket("memfd");
+    let sink = JournaldSink::at(&path).unwrap();
+
+    let message = "x".repeat(4 * 1024 * 1024);
+    sink.log(&LogRecord::new(LogLevel::Error, "Dump", message.as_str()))
+        .unwrap();
+
+    // Receive the descriptor passed along with the empty datagram
+    let fd_len = std::mem::size_of::<RawFd>() as libc::c_uint;
+    let mut control = vec![0u8; unsafe { libc::CMSG_SPACE(fd_len) } as usize];
+    let mut data = [0u8; 16];
+    let mut iov = libc::iovec {
+        iov_base: data.as_mut_ptr().cast(),
+        iov_len: data.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = control.len() as _;
+
+    let mut file = unsafe {
+        use std::os::fd::AsRawFd;
+
+        assert_eq!(libc::recvmsg(listener.as_raw_fd(), &mut msg, 0), 0);
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        assert!(!cmsg.is_null());
+        assert_eq!((*cmsg).cmsg_type, libc::SCM_RIGHTS);
+        File::from_raw_fd(std::ptr::read_unaligned(
+            libc::CMSG_DATA(cmsg).cast::<RawFd>(),
+        ))
+    };
+
+    // The descriptor shares the offset the sink left at the end of the entry
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut entry = Vec::new();
+    file.read_to_end(&mut entry).unwrap();
+    let fields = parse(&entry);
+    assert_eq!(field(&fields, "MESSAGE").map(str::len), Some(message.len()));
+    assert_eq!(field(&fields, "PRIORITY"), Some("3"));
+
+    std::fs::remove_file(&path).unwrap();
+}
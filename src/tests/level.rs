@@ -0,0 +1,70 @@
+use std::fs;
+
+use crate::{
+    logger::{LogInterval, LogLevel, Logger},
+    record::LogRecord,
+};
+
+#[test]
+fn levels_are_ordered_by_severity() {
+    let mut levels = vec![
+        LogLevel::Error,
+        LogLevel::Success,
+        LogLevel::Trace,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+    ];
+    levels.sort();
+
+    assert_eq!(
+        levels,
+        [
+            LogLevel::Trace,
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Success,
+            LogLevel::Warn,
+            LogLevel::Error,
+        ]
+    );
+}
+
+#[test]
+fn levels_display_and_parse() {
+    assert_eq!(LogLevel::Success.to_string(), "success");
+    assert_eq!(format!("{:<7}|", LogLevel::Warn), "warn   |");
+    assert_eq!("TRACE".parse(), Ok(LogLevel::Trace));
+    assert_eq!("Warning".parse(), Ok(LogLevel::Warn));
+    assert!("loud".parse::<LogLevel>().is_err());
+}
+
+#[test]
+fn logger_skips_levels_below_the_threshold() {
+    let dir = std::env::temp_dir().join(format!("tlogger-level-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_min_level(LogLevel::Success);
+
+    for level in [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Success,
+        LogLevel::Warn,
+        LogLevel::Error,
+    ] {
+        logger
+            .log(&LogRecord::new(level, "Level", level.to_string()))
+            .unwrap();
+    }
+
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let contents = fs::read_to_string(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let levels: Vec<&str> = contents
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap())
+        .collect();
+    assert_eq!(levels, ["SUCCESS", "WARN", "ERROR"]);
+}
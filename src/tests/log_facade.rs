@@ -0,0 +1,47 @@
+use log::Level;
+
+use crate::{format_console, prelude::*};
+
+#[test]
+fn log_levels_map_onto_tlogger_levels() {
+    assert_eq!(LogLevel::from(Level::Error), LogLevel::Error);
+    assert_eq!(LogLevel::from(Level::Warn), LogLevel::Warn);
+    assert_eq!(LogLevel::from(Level::Info), LogLevel::Info);
+    assert_eq!(LogLevel::from(Level::Debug), LogLevel::Debug);
+    assert_eq!(LogLevel::from(Level::Trace), LogLevel::Trace);
+}
+
+#[test]
+fn log_macros_reach_tlogger() {
+    let _globals = super::lock_globals();
+    install_log_facade(log::LevelFilter::Debug).unwrap();
+
+    let records = capture_logs(|| {
+        log::info!(target: "Dependency", "Connected to {}", "localhost");
+        log::warn!("Retrying in {}s", 5);
+        log::debug!("reusing idle_connection for pool_key a*b*c");
+        log::trace!("Filtered out by the max level");
+    });
+
+    assert_eq!(records.len(), 3);
+    assert_logged!(records => Info, title = "Dependency", message = "Connected to localhost");
+    assert_logged!(records => Warn, title = module_path!(), message = "Retrying in 5s");
+    assert_not_logged!(records => Trace);
+
+    let callsite = records[1].callsite.unwrap();
+    assert_eq!(callsite.module_path, module_path!());
+    assert_eq!(callsite.file, file!());
+
+    // Markup characters in messages from other crates are left alone
+    let debug = &records[2];
+    assert!(debug.raw);
+    assert_eq!(
+        debug.plain_message(),
+        "reusing idle_connection for pool_key a*b*c"
+    );
+    assert!(LogFormat::Text
+        .format(debug)
+        .ends_with("│ tlogger::tests::log_facade reusing idle_connection for pool_key a*b*c\n"));
+    assert!(strip_ansi_codes(&format_console(debug))
+        .contains("reusing idle_connection for pool_key a*b*c"));
+}
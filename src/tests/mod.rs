@@ -1,3 +1,52 @@
+#[cfg(test)]
+mod background;
+#[cfg(test)]
+mod capture;
+#[cfg(test)]
+mod fields;
+#[cfg(test)]
+mod filter;
+#[cfg(test)]
+mod format;
+#[cfg(test)]
+mod global;
+#[cfg(test)]
+mod instance;
+#[cfg(test)]
+mod interval;
+#[cfg(all(test, target_os = "linux"))]
+mod journald;
+#[cfg(test)]
+mod level;
+#[cfg(all(test, feature = "log"))]
+mod log_facade;
+#[cfg(test)]
+mod network;
+#[cfg(test)]
+mod panic;
+#[cfg(test)]
+mod record;
+#[cfg(test)]
+mod ring;
+#[cfg(test)]
+mod rotation;
+#[cfg(test)]
+mod sinks;
+#[cfg(test)]
+mod syslog;
+#[cfg(all(test, feature = "tracing"))]
+mod tracing_layer;
+
+/// Held by tests that change the global logger or sinks, or check what reaches them
+#[cfg(test)]
+static GLOBALS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Serializes tests that rely on global settings, surviving a test that panicked
+#[cfg(test)]
+fn lock_globals() -> std::sync::MutexGuard<'static, ()> {
+    GLOBALS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 #[cfg(test)]
 #[test]
 pub fn info() {
@@ -5,7 +54,10 @@ pub fn info() {
 
     use super::prelude::*;
 
-    init_logger("Logs", LogInterval::OneHour).unwrap();
+    let _globals = lock_globals();
+    let _capture = LogCapture::start().quiet();
+    let dir = std::env::temp_dir().join(format!("tlogger-info-{}", std::process::id()));
+    init_logger(&dir, LogInterval::OneHour).unwrap();
     clear_log_levels();
     add_log_levels!(LogLevel::Debug, LogLevel::Warn, LogLevel::Error);
 
@@ -28,6 +80,7 @@ pub fn info() {
     info!("Server", "Starting");
     success!("Login", "User _{}_ connected", "Alice");
     debug!("Processing", "Items in queue: {}", 42);
+    trace!("Processing", "Polled queue");
     warn!("Memory", "Usage at {}%", 85);
     error!("Database", "* **Connection _failed_***");
 
@@ -54,4 +107,11 @@ pub fn info() {
         "Complex",
         "**Bold *italic* _underlined_ ~strikethrough~ !blinking!**"
     );
+
+    assert_logged!(Warn, title = "Memory", contains = "85%");
+    assert_logged!(Error, title = "Database", contains = "Connection failed");
+    assert_not_logged!(Error, title = "Memory");
+
+    reset();
+    let _ = std::fs::remove_dir_all(&dir);
 }
@@ -0,0 +1,208 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, UdpSocket},
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    logger::LogLevel,
+    opts::{add_sink, clear_sinks, flush_sinks},
+    record::LogRecord,
+    sinks::{Filtered, NetworkSink, Sink},
+};
+
+/// An address nothing listens on, that a collector can be started on later
+fn unused_addr() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Accepts the first connection of the sink and reads `count` lines from it
+fn receive(listener: &TcpListener, count: usize) -> Vec<String> {
+    listener.set_nonblocking(true).unwrap();
+    let started = Instant::now();
+    let stream = loop {
+        match listener.accept() {
+            Ok((stream, _)) => break stream,
+            Err(_) if started.elapsed() < Duration::from_secs(5) => {
+                thread::sleep(Duration::from_millis(10))
+            }
+            Err(e) => panic!("the sink did not connect: {e}"),
+        }
+    };
+    stream.set_nonblocking(false).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    BufReader::new(stream)
+        .lines()
+        .take(count)
+        .map(Result::unwrap)
+        .collect()
+}
+
+fn log(sink: &NetworkSink, level: LogLevel, message: &str) {
+    let record = LogRecord::new(level, "NetworkTest", message);
+    if sink.enabled(&level) {
+        sink.log(&record).unwrap();
+    }
+}
+
+#[test]
+fn records_are_sent_as_json_datagrams() {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sink = NetworkSink::udp(listener.local_addr().unwrap())
+        .unwrap()
+        .with_min_level(LogLevel::Warn);
+
+    log(&sink, LogLevel::Info, "Filtered out");
+    log(&sink, LogLevel::Error, "Connection failed");
+
+    let mut buf = [0; 1024];
+    let len = listener.recv(&mut buf).unwrap();
+    let line = String::from_utf8_lossy(&buf[..len]);
+    assert!(line.starts_with("{\"timestamp\":"));
+    assert!(line.contains("\"level\":\"error\""));
+    assert!(line.ends_with("\"message\":\"Connection failed\"}\n"));
+}
+
+#[test]
+fn records_are_spooled_to_disk_until_the_collector_is_back() {
+    let addr = unused_addr();
+    let spool = std::env::temp_dir().join(format!("tlogger-network-{}.spool", std::process::id()));
+    _ = fs::remove_file(&spool);
+
+    let sink = NetworkSink::tcp(addr)
+        .unwrap()
+        .with_disk_spool(&spool)
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+    log(&sink, LogLevel::Info, "First");
+    log(&sink, LogLevel::Warn, "Second");
+
+    let started = Instant::now();
+    while fs::read_to_string(&spool).map_or(0, |spooled| spooled.lines().count()) < 2 {
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "records were not spooled"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    let listener = TcpListener::bind(addr).unwrap();
+    log(&sink, LogLevel::Error, "Third");
+    let lines = receive(&listener, 3);
+    sink.flush().unwrap();
+
+    let messages: Vec<_> = lines
+        .iter()
+        .map(|line| line.split("\"message\":").nth(1).unwrap())
+        .collect();
+    assert_eq!(messages, ["\"First\"}", "\"Second\"}", "\"Third\"}"]);
+    assert!(!spool.exists());
+}
+
+#[test]
+fn the_oldest_records_are_dropped_when_the_queue_is_full() {
+    let addr = unused_addr();
+    let sink = NetworkSink::tcp(addr)
+        .unwrap()
+        .with_capacity(2)
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+    for i in 1..=5 {
+        log(&sink, LogLevel::Info, &format!("Record {i}"));
+    }
+
+    let listener = TcpListener::bind(addr).unwrap();
+    let lines = receive(&listener, 3);
+
+    assert!(lines[0].contains("Dropped 3 log records while the collector was unreachable"));
+    assert!(lines[1].contains("Record 4"));
+    assert!(lines[2].contains("Record 5"));
+}
+
+#[test]
+fn the_spool_file_stops_growing_at_its_limit() {
+    let spool = std::env::temp_dir().join(format!(
+        "tlogger-network-limit-{}.spool",
+        std::process::id()
+    ));
+    _ = fs::remove_file(&spool);
+
+    let sink = NetworkSink::tcp(unused_addr())
+        .unwrap()
+        .with_disk_spool(&spool)
+        .with_spool_limit(1024)
+        .with_backoff(Duration::from_millis(10), Duration::from_millis(50));
+    for i in 0..100 {
+        log(&sink, LogLevel::Info, &format!("Record {i}"));
+    }
+    drop(sink);
+
+    let spooled = fs::read_to_string(&spool).unwrap();
+    fs::remove_file(&spool).unwrap();
+    assert!(spooled.len() <= 1024);
+    assert!(spooled.lines().count() > 0);
+}
+
+#[test]
+fn queued_records_are_sent_at_exit() {
+    // Run again in a child process, which logs and exits long before the next retry
+    if let Ok(addr) = std::env::var("TLOGGER_COLLECTOR") {
+        clear_sinks();
+        add_sink(
+            NetworkSink::tcp(addr)
+                .unwrap()
+                .with_backoff(Duration::from_secs(60), Duration::from_secs(60)),
+        );
+        let log = |i| {
+            crate::log_record(&LogRecord::new(
+                LogLevel::Info,
+                "NetworkTest",
+                format!("Record {i}"),
+            ))
+        };
+        log(0);
+        // Returns once the collector turned out to be unreachable
+        flush_sinks().unwrap();
+        println!("unreachable");
+        std::io::stdin().read_line(&mut String::new()).unwrap();
+        for i in 1..=1000 {
+            log(i);
+        }
+        std::process::exit(0);
+    }
+
+    let addr = unused_addr();
+    let mut child = Command::new(std::env::current_exe().unwrap())
+        .args([
+            "tests::network::queued_records_are_sent_at_exit",
+            "--exact",
+            "--nocapture",
+            "--test-threads=1",
+        ])
+        .env("TLOGGER_COLLECTOR", addr.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    for line in stdout.lines() {
+        if line.unwrap().ends_with("unreachable") {
+            break;
+        }
+    }
+
+    let listener = TcpListener::bind(addr).unwrap();
+    writeln!(child.stdin.take().unwrap()).unwrap();
+    let lines = receive(&listener, usize::MAX);
+
+    assert!(child.wait().unwrap().success());
+    assert_eq!(lines.len(), 1001);
+    assert!(lines[0].contains("\"message\":\"Record 0\""));
+    assert!(lines[1000].contains("\"message\":\"Record 1000\""));
+}
@@ -0,0 +1,121 @@
+use std::{
+    fs, io,
+    sync::{mpsc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    logger::{read_log_file, LogInterval, LogLevel},
+    opts::{add_sink, init_logger, install_panic_hook, reset},
+    record::LogRecord,
+    sinks::{RingBufferSink, Sink},
+    write, SINKS,
+};
+
+/// Runs `f` on a thread that is expected to panic, failing if the panic hook hangs
+fn panic_on_thread<F: FnOnce() + Send + 'static>(f: F) {
+    let (done, finished) = mpsc::channel();
+    thread::Builder::new()
+        .name("worker".to_string())
+        .spawn(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+            done.send(result.is_err()).unwrap();
+        })
+        .unwrap();
+    let panicked = finished
+        .recv_timeout(Duration::from_secs(10))
+        .expect("the panic hook deadlocked");
+    assert!(panicked);
+}
+
+#[test]
+fn panics_are_logged_and_written_to_file() {
+    let _globals = super::lock_globals();
+    let dir = std::env::temp_dir().join(format!("tlogger-panic-{}", std::process::id()));
+    _ = fs::remove_dir_all(&dir);
+
+    init_logger(&dir, LogInterval::OneDay).unwrap();
+    let recent = RingBufferSink::new(10);
+    add_sink(recent.clone());
+    install_panic_hook();
+    install_panic_hook();
+
+    let result = thread::Builder::new()
+        .name("worker".to_string())
+        .spawn(|| panic!("Queue {} of pool_key a*b*c is corrupt", 7))
+        .unwrap()
+        .join();
+    assert!(result.is_err());
+
+    let panics = recent.query().title("Panic").records();
+    reset();
+
+    assert_eq!(panics.len(), 1);
+    let panic = &panics[0];
+    assert_eq!(panic.level, LogLevel::Error);
+    assert!(panic.boxed);
+    assert!(panic.raw);
+    assert!(panic
+        .message
+        .starts_with("Queue 7 of pool_key a*b*c is corrupt"));
+    assert_eq!(panic.fields[0], ("thread", "worker".to_string()));
+    assert_eq!(panic.fields[1].0, "location");
+    assert!(panic.fields[1].1.starts_with("src/tests/panic.rs:"));
+
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let contents = read_log_file(file).unwrap();
+    assert!(contents.contains("│ Panic Queue 7 of pool_key a*b*c is corrupt"));
+    assert!(contents.contains("thread=worker"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Panics while writing "Boom" records, holding its lock
+struct PanickingSink(Mutex<()>);
+
+impl Sink for PanickingSink {
+    fn log(&self, record: &LogRecord) -> io::Result<()> {
+        let _locked = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        if record.title == "Boom" {
+            panic!("Sink failed");
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn panics_inside_a_sink_skip_that_sink() {
+    let _globals = super::lock_globals();
+    let recent = RingBufferSink::new(10);
+    add_sink(recent.clone());
+    add_sink(PanickingSink(Mutex::new(())));
+    install_panic_hook();
+
+    panic_on_thread(|| crate::log_record(&LogRecord::new(LogLevel::Info, "Boom", "Write")));
+
+    let panics = recent.query().title("Panic").records();
+    reset();
+    assert_eq!(panics.len(), 1);
+    assert!(panics[0].message.starts_with("Sink failed"));
+}
+
+#[test]
+fn panics_while_the_sinks_are_locked_still_reach_the_file() {
+    let _globals = super::lock_globals();
+    let dir = std::env::temp_dir().join(format!("tlogger-panic-locked-{}", std::process::id()));
+    _ = fs::remove_dir_all(&dir);
+    init_logger(&dir, LogInterval::OneDay).unwrap();
+    install_panic_hook();
+
+    panic_on_thread(|| {
+        let _sinks = write(&SINKS);
+        panic!("Sinks are being replaced");
+    });
+
+    reset();
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let contents = read_log_file(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(contents.contains("│ Panic Sinks are being replaced"));
+}
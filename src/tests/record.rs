@@ -0,0 +1,54 @@
+use std::fs;
+
+use crate::{
+    format::LogFormat,
+    format_console,
+    logger::{LogInterval, LogLevel, Logger},
+    record::LogRecord,
+    strip_ansi_codes,
+};
+
+#[test]
+fn file_output_comes_from_the_record() {
+    let dir = std::env::temp_dir().join(format!("tlogger-record-{}", std::process::id()));
+    let logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+
+    let record = LogRecord::new(LogLevel::Warn, "Memory", "Usage at **85%** ✔")
+        .with_fields(vec![("host", "db 1".to_string())]);
+    logger.log(&record).unwrap();
+
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let contents = fs::read_to_string(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert!(contents.starts_with("WARN "));
+    assert!(contents.ends_with("│ Memory Usage at 85% ✔ host=\"db 1\"\n"));
+}
+
+#[test]
+fn raw_messages_keep_their_markup_characters() {
+    let message = "reusing idle_connection for pool_key a*b*c **as is**";
+    let record = LogRecord::new(LogLevel::Info, "Pool", message).raw(true);
+
+    assert_eq!(record.plain_message(), message);
+    assert!(LogFormat::Text
+        .format(&record)
+        .ends_with(&format!("│ Pool {message}\n")));
+    assert!(LogFormat::Json
+        .format(&record)
+        .ends_with(&format!("\"message\":\"{message}\"}}\n")));
+    assert!(LogFormat::Logfmt
+        .format(&record)
+        .ends_with(&format!("msg=\"{message}\"\n")));
+    assert!(strip_ansi_codes(&format_console(&record)).contains(message));
+    assert!(
+        strip_ansi_codes(&format_console(&record.clone().boxed(true)))
+            .contains("reusing idle_connection for pool_key a*b*c **as is**")
+    );
+
+    let styled = LogRecord::new(LogLevel::Info, "Pool", message);
+    assert_eq!(
+        styled.plain_message(),
+        "reusing idleconnection for poolkey abc as is"
+    );
+}
@@ -0,0 +1,101 @@
+use chrono::{Duration, Local};
+
+use crate::{
+    logger::LogLevel,
+    record::LogRecord,
+    sinks::{Filtered, RingBufferSink, Sink, SinkFormat},
+    strip_ansi_codes,
+};
+
+fn record(level: LogLevel, title: &str, message: &str, minutes_ago: i64) -> LogRecord {
+    let mut record = LogRecord::new(level, title, message);
+    record.timestamp = Local::now() - Duration::minutes(minutes_ago);
+    record
+}
+
+#[test]
+fn keeps_only_the_most_recent_records() {
+    let ring = RingBufferSink::new(3).with_min_level(LogLevel::Info);
+    assert!(!ring.enabled(&LogLevel::Debug));
+
+    for i in 1..=5 {
+        ring.log(&record(LogLevel::Info, "Queue", &format!("Record {i}"), 0))
+            .unwrap();
+    }
+
+    let messages: Vec<_> = ring.records().into_iter().map(|r| r.message).collect();
+    assert_eq!(messages, ["Record 3", "Record 4", "Record 5"]);
+    ring.clear();
+    assert!(ring.is_empty());
+}
+
+#[test]
+fn queries_filter_by_level_title_and_time() {
+    let ring = RingBufferSink::new(100);
+    ring.log(&record(LogLevel::Warn, "Memory", "Usage at 80%", 30))
+        .unwrap();
+    ring.log(&record(LogLevel::Info, "Memory", "Usage at 50%", 20))
+        .unwrap();
+    ring.log(&record(LogLevel::Warn, "Memory", "Usage at 85%", 10))
+        .unwrap();
+    ring.log(&record(LogLevel::Error, "Database", "Connection failed", 5))
+        .unwrap();
+
+    let messages = |records: Vec<LogRecord>| -> Vec<String> {
+        records.into_iter().map(|record| record.message).collect()
+    };
+
+    assert_eq!(
+        messages(
+            ring.query()
+                .min_level(LogLevel::Warn)
+                .title("Memory")
+                .records()
+        ),
+        ["Usage at 80%", "Usage at 85%"]
+    );
+    assert_eq!(
+        messages(
+            ring.query()
+                .levels([LogLevel::Info, LogLevel::Error])
+                .records()
+        ),
+        ["Usage at 50%", "Connection failed"]
+    );
+    assert_eq!(
+        messages(
+            ring.query()
+                .since(Local::now() - Duration::minutes(25))
+                .until(Local::now() - Duration::minutes(7))
+                .records()
+        ),
+        ["Usage at 50%", "Usage at 85%"]
+    );
+    assert_eq!(
+        messages(ring.query().title("Memory").last(1).records()),
+        ["Usage at 85%"]
+    );
+}
+
+#[test]
+fn exports_as_console_lines_or_json() {
+    let ring = RingBufferSink::new(10);
+    ring.log(&record(LogLevel::Warn, "Memory", "Usage at **85%**", 0).boxed(true))
+        .unwrap();
+    ring.log(&record(LogLevel::Error, "Database", "Connection failed", 0))
+        .unwrap();
+
+    let styled = strip_ansi_codes(&ring.export(SinkFormat::Styled));
+    let lines: Vec<_> = styled.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].ends_with("Memory Usage at 85%"));
+    assert!(lines[1].ends_with("Database Connection failed"));
+
+    let json = ring
+        .query()
+        .min_level(LogLevel::Error)
+        .export(SinkFormat::Json);
+    assert_eq!(json.lines().count(), 1);
+    assert!(json.starts_with("{\"timestamp\":"));
+    assert!(json.contains("\"message\":\"Connection failed\""));
+}
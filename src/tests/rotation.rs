@@ -0,0 +1,281 @@
+use std::fs;
+
+use crate::{
+    logger::{read_log_file, Compression, FileNaming, LogInterval, LogLevel, Logger, Retention},
+    record::LogRecord,
+};
+
+fn file_names(dir: &std::path::Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn rotates_by_size_and_on_demand() {
+    let dir = std::env::temp_dir().join(format!("tlogger-rotation-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_max_file_size(170);
+
+    let record = LogRecord::new(LogLevel::Info, "Server", "x".repeat(40));
+    for _ in 0..4 {
+        logger.log(&record).unwrap();
+    }
+    logger.rotate_now().unwrap();
+    logger.log(&record).unwrap();
+
+    let names = file_names(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(names.len(), 3);
+    assert!(names[0].ends_with("-00h-24h.1.log"));
+    assert!(names[1].ends_with("-00h-24h.2.log"));
+    assert!(names[2].ends_with("-00h-24h.log"));
+}
+
+#[test]
+fn prunes_old_files_on_rotation() {
+    let dir = std::env::temp_dir().join(format!("tlogger-retention-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let now = std::time::SystemTime::now();
+    let day = std::time::Duration::from_secs(24 * 60 * 60);
+    for (name, age) in [
+        ("2000-01-01-00h-24h.log", 40),
+        ("2000-01-02-00h-24h.log", 3),
+        ("2000-01-03-00h-24h.jsonl", 2),
+        ("2000-01-04-00h-24h.log", 1),
+        ("2000-01-01-00h-24h.1.log.gz", 50),
+        ("2000-W01.log", 45),
+        ("2000-01-05-10h15m30s-10h16m00s+0100.log", 35),
+        // Files of other programs are left alone
+        ("notes.txt", 100),
+        ("access.log", 100),
+        ("app.2000-01-01.jsonl", 100),
+    ] {
+        let file = fs::File::create(dir.join(name)).unwrap();
+        file.set_modified(now - day * age).unwrap();
+    }
+
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_retention(Retention {
+        max_age_days: Some(30),
+        max_files: Some(3),
+        ..Default::default()
+    });
+    logger
+        .log(&LogRecord::new(LogLevel::Info, "Server", "Starting"))
+        .unwrap();
+
+    let names = file_names(&dir);
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(names.len(), 6);
+    assert_eq!(names[0], "2000-01-03-00h-24h.jsonl");
+    assert_eq!(names[1], "2000-01-04-00h-24h.log");
+    assert!(names[2].ends_with("-00h-24h.log"));
+    assert_eq!(
+        names[3..],
+        ["access.log", "app.2000-01-01.jsonl", "notes.txt"]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn stops_writing_debug_and_info_when_low_on_disk() {
+    let dir = std::env::temp_dir().join(format!("tlogger-disk-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_min_free_disk(u64::MAX);
+
+    logger
+        .log(&LogRecord::new(LogLevel::Info, "Server", "Starting"))
+        .unwrap();
+    logger
+        .log(&LogRecord::new(
+            LogLevel::Error,
+            "Database",
+            "Connection failed",
+        ))
+        .unwrap();
+
+    let file = dir.join(&file_names(&dir)[0]);
+    let contents = fs::read_to_string(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("WARN") && lines[0].contains("│ Logger Less than"));
+    assert!(lines[1].ends_with("│ Database Connection failed"));
+}
+
+#[test]
+fn compresses_rotated_files() {
+    let dir = std::env::temp_dir().join(format!("tlogger-compression-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    // Left behind uncompressed by an earlier run
+    fs::write(dir.join("2000-01-01-00h-24h.log"), "INFO    old\n").unwrap();
+    // Left behind by a compression an earlier run didn't finish
+    fs::write(dir.join("2000-01-02-00h-24h.log.gz.tmp"), "partial").unwrap();
+    fs::write(dir.join("access.log"), "GET /\n").unwrap();
+
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.set_compression(Compression::Gzip).unwrap();
+
+    let record = LogRecord::new(LogLevel::Info, "Server", "Starting");
+    logger.log(&record).unwrap();
+    logger.rotate_now().unwrap();
+    logger.log(&record).unwrap();
+
+    // Waits for the background compression to finish
+    drop(logger);
+
+    let names = file_names(&dir);
+    let old = read_log_file(dir.join("2000-01-01-00h-24h.log.gz")).unwrap();
+    let rotated = read_log_file(dir.join(&names[2])).unwrap();
+    let foreign = fs::read_to_string(dir.join("access.log")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(names.len(), 4);
+    assert_eq!(names[0], "2000-01-01-00h-24h.log.gz");
+    assert!(names[1].ends_with("-00h-24h.1.log"));
+    assert!(names[2].ends_with("-00h-24h.log.gz"));
+    assert_eq!(names[3], "access.log");
+    assert_eq!(old, "INFO    old\n");
+    assert!(rotated.ends_with("│ Server Starting\n"));
+    assert_eq!(foreign, "GET /\n");
+}
+
+#[test]
+fn concurrent_writes_keep_lines_whole() {
+    let dir = std::env::temp_dir().join(format!("tlogger-concurrent-{}", std::process::id()));
+    let logger = std::sync::Arc::new(Logger::new(&dir, LogInterval::OneDay).unwrap());
+
+    let threads: Vec<_> = (0..8)
+        .map(|thread| {
+            let logger = std::sync::Arc::clone(&logger);
+            std::thread::spawn(move || {
+                for i in 0..200 {
+                    let message = format!("{}-{} {}", thread, i, "x".repeat(500));
+                    logger
+                        .log(&LogRecord::new(LogLevel::Info, "Thread", message))
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let file = dir.join(&file_names(&dir)[0]);
+    let contents = fs::read_to_string(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let lines: Vec<&str> = contents.lines().collect();
+    assert_eq!(lines.len(), 8 * 200);
+    for line in lines {
+        assert!(line.starts_with("INFO") && line.ends_with(&"x".repeat(500)));
+    }
+}
+
+#[test]
+fn names_files_after_the_naming_template() {
+    let dir = std::env::temp_dir().join(format!("tlogger-naming-{}", std::process::id()));
+    fs::create_dir_all(dir.join("2000/01/01")).unwrap();
+    let old = fs::File::create(dir.join("2000/01/01/app-2000-01-01.txt")).unwrap();
+    old.set_modified(
+        std::time::SystemTime::now() - std::time::Duration::from_secs(40 * 24 * 60 * 60),
+    )
+    .unwrap();
+
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger
+        .set_file_naming(FileNaming {
+            prefix: Some("app-".to_string()),
+            pattern: Some("%Y-%m-%d".to_string()),
+            extension: Some("txt".to_string()),
+            directories: Some("%Y/%m/%d".to_string()),
+            latest_link: Some("latest.txt".to_string()),
+        })
+        .unwrap();
+    logger.set_retention(Retention {
+        max_age_days: Some(30),
+        ..Default::default()
+    });
+
+    let record = LogRecord::new(LogLevel::Info, "Server", "Starting");
+    logger.log(&record).unwrap();
+    logger.rotate_now().unwrap();
+    logger.log(&record).unwrap();
+
+    let now = chrono::Local::now();
+    let day_dir = dir.join(now.format("%Y/%m/%d").to_string());
+    let date = now.format("%Y-%m-%d");
+    let names = file_names(&day_dir);
+    let old_removed = !dir.join("2000").exists();
+    #[cfg(unix)]
+    let latest = fs::read_link(dir.join("latest.txt")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(
+        names,
+        [format!("app-{}.1.txt", date), format!("app-{}.txt", date)]
+    );
+    assert!(old_removed);
+    #[cfg(unix)]
+    assert_eq!(
+        latest,
+        std::path::Path::new(&format!("{}/app-{}.1.txt", now.format("%Y/%m/%d"), date))
+    );
+}
+
+#[test]
+fn prunes_files_in_directories_of_the_naming_pattern() {
+    let dir = std::env::temp_dir().join(format!("tlogger-naming-nested-{}", std::process::id()));
+    fs::create_dir_all(dir.join("2000/01")).unwrap();
+    let old = fs::File::create(dir.join("2000/01/app-01_10.log")).unwrap();
+    old.set_modified(
+        std::time::SystemTime::now() - std::time::Duration::from_secs(40 * 24 * 60 * 60),
+    )
+    .unwrap();
+
+    let mut logger = Logger::new(&dir, LogInterval::OneHour).unwrap();
+    logger
+        .set_file_naming(FileNaming {
+            prefix: Some("app-".to_string()),
+            pattern: Some("%Y/%m/%d_%H".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    logger.set_retention(Retention {
+        max_age_days: Some(30),
+        ..Default::default()
+    });
+    logger
+        .log(&LogRecord::new(LogLevel::Info, "Server", "Starting"))
+        .unwrap();
+
+    let now = chrono::Local::now();
+    let names = file_names(&dir.join(now.format("%Y/%m").to_string()));
+    let old_removed = !dir.join("2000").exists();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(names, [format!("app-{}.log", now.format("%d_%H"))]);
+    assert!(old_removed);
+}
+
+#[test]
+fn rejects_invalid_naming_patterns() {
+    let dir = std::env::temp_dir().join(format!("tlogger-naming-invalid-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    let result = logger.set_file_naming(FileNaming {
+        pattern: Some("%Y-%Q".to_string()),
+        ..Default::default()
+    });
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(result.unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+}
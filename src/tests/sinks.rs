@@ -0,0 +1,78 @@
+use std::fs;
+
+use crate::{
+    format::LogFormat,
+    log_record,
+    logger::{LogInterval, LogLevel, Logger},
+    opts::{add_sink, reset},
+    record::LogRecord,
+    sinks::{Filtered, Formatted, MemorySink, Sink, SinkFormat, WriterSink},
+};
+
+#[test]
+fn sinks_filter_levels_and_format_on_their_own() {
+    let _globals = super::lock_globals();
+    let everything = MemorySink::new();
+    let errors = MemorySink::new()
+        .with_levels([LogLevel::Error])
+        .with_format(SinkFormat::Json);
+    add_sink(everything.clone());
+    add_sink(errors.clone());
+
+    log_record(&LogRecord::new(LogLevel::Info, "SinkTest", "Starting"));
+    log_record(&LogRecord::new(
+        LogLevel::Error,
+        "SinkTest",
+        "Connection failed",
+    ));
+
+    // Other tests log through the same sinks at the same time
+    let ours = |lines: Vec<String>| -> Vec<String> {
+        lines
+            .into_iter()
+            .filter(|line| line.contains("SinkTest"))
+            .collect()
+    };
+    let everything = ours(everything.lines());
+    let errors = ours(errors.lines());
+    reset();
+
+    assert_eq!(everything.len(), 2);
+    assert!(everything[0].starts_with("INFO") && everything[0].ends_with("│ SinkTest Starting"));
+    assert!(everything[1].starts_with("ERROR"));
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].starts_with("{\"timestamp\":"));
+    assert!(errors[0].contains("\"message\":\"Connection failed\""));
+}
+
+#[test]
+fn loggers_and_writers_are_sinks() {
+    let dir = std::env::temp_dir().join(format!("tlogger-sinks-{}", std::process::id()));
+    let mut logger = Logger::new(&dir, LogInterval::OneDay).unwrap();
+    logger.clear_log_levels();
+    logger.add_log_level(LogLevel::Warn);
+    logger.set_format(LogFormat::Logfmt);
+    let writer = WriterSink::new(Vec::new()).with_format(SinkFormat::Logfmt);
+
+    let sinks: [&dyn Sink; 2] = [&logger, &writer];
+    for record in [
+        LogRecord::new(LogLevel::Info, "Server", "Starting"),
+        LogRecord::new(LogLevel::Warn, "Memory", "Usage at 85%"),
+    ] {
+        for sink in sinks {
+            if sink.enabled(&record.level) {
+                sink.log(&record).unwrap();
+            }
+        }
+    }
+
+    let written = String::from_utf8(writer.into_inner()).unwrap();
+    let file = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let contents = fs::read_to_string(file).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(contents.lines().count(), 1);
+    assert!(contents.contains("level=warn title=Memory"));
+    assert_eq!(written.lines().count(), 2);
+    assert!(written.contains("level=info title=Server msg=Starting"));
+}
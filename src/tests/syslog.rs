@@ -0,0 +1,141 @@
+use std::{
+    io::Read,
+    net::{TcpListener, UdpSocket},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    logger::LogLevel,
+    record::LogRecord,
+    sinks::{Facility, Filtered, Sink, SyslogFormat, SyslogSink},
+};
+
+fn record() -> LogRecord {
+    LogRecord::new(LogLevel::Warn, "Memory", "Usage at **85%**").with_fields(vec![
+        ("usage", "85".to_string()),
+        ("host", "db \"1\"".to_string()),
+    ])
+}
+
+/// Splits a TCP stream into its octet counted messages
+fn frames(mut received: &str) -> Vec<&str> {
+    let mut frames = Vec::new();
+    while let Some((len, rest)) = received.split_once(' ') {
+        let len: usize = len.parse().unwrap();
+        frames.push(&rest[..len]);
+        received = &rest[len..];
+    }
+    assert!(received.is_empty(), "trailing bytes: {received:?}");
+    frames
+}
+
+#[test]
+fn rfc5424_messages_over_udp() {
+    let listener = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let sink = SyslogSink::udp(listener.local_addr().unwrap())
+        .unwrap()
+        .with_facility(Facility::Local0)
+        .with_hostname("web-1")
+        .with_app_name("my-app");
+    sink.log(&record()).unwrap();
+
+    let mut buf = [0; 1024];
+    let len = listener.recv(&mut buf).unwrap();
+    let message = String::from_utf8_lossy(&buf[..len]);
+
+    // local0 * 8 + warning
+    assert!(message.starts_with("<132>1 "), "{message}");
+    let parts: Vec<&str> = message.splitn(8, ' ').collect();
+    assert_eq!(
+        parts[2..6],
+        ["web-1", "my-app", &std::process::id().to_string(), "Memory"]
+    );
+    assert_eq!(
+        parts[6..].join(" "),
+        r#"[tlogger@32473 title="Memory" usage="85" host="db \"1\""] Usage at 85%"#
+    );
+}
+
+#[test]
+fn rfc3164_messages_over_tcp_survive_a_dropped_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let sink = SyslogSink::tcp(listener.local_addr().unwrap())
+        .unwrap()
+        .with_format(SyslogFormat::Rfc3164)
+        .with_hostname("web-1")
+        .with_app_name("my-app");
+
+    // The daemon drops the first connection, a write only fails once the reset
+    // arrives, after which the sink reconnects
+    drop(listener.accept().unwrap());
+    listener.set_nonblocking(true).unwrap();
+    let record = LogRecord::new(LogLevel::Error, "Database", "Connection failed\nRetrying");
+    let mut stream = None;
+    for _ in 0..100 {
+        _ = sink.log(&record);
+        if let Ok((accepted, _)) = listener.accept() {
+            stream = Some(accepted);
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    drop(sink);
+
+    let mut stream = stream.expect("the sink reconnected");
+    stream.set_nonblocking(false).unwrap();
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+    // Messages are octet counted, so their lines stay together
+    let message = *frames(&received).last().unwrap();
+    assert!(message.starts_with("<11>"), "{message}");
+    assert!(message.ends_with(&format!(
+        " web-1 my-app[{}]: Database: Connection failed\nRetrying",
+        std::process::id()
+    )));
+}
+
+#[test]
+fn rfc5424_messages_over_tcp_are_octet_counted() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let sink = SyslogSink::tcp(listener.local_addr().unwrap()).unwrap();
+    let (mut stream, _) = listener.accept().unwrap();
+
+    sink.log(&record()).unwrap();
+    drop(sink);
+
+    let mut received = String::new();
+    stream.read_to_string(&mut received).unwrap();
+    let frames = frames(&received);
+    assert_eq!(frames.len(), 1);
+    assert!(frames[0].starts_with("<12>1 "));
+}
+
+#[cfg(unix)]
+#[test]
+fn messages_over_unix_datagram_sockets() {
+    use std::os::unix::net::UnixDatagram;
+
+    let path = std::env::temp_dir().join(format!("tlogger-syslog-{}.sock", std::process::id()));
+    _ = std::fs::remove_file(&path);
+    let listener = UnixDatagram::bind(&path).unwrap();
+
+    let sink = SyslogSink::unix(&path)
+        .unwrap()
+        .with_min_level(LogLevel::Info);
+    assert!(!sink.enabled(&LogLevel::Debug));
+    sink.log(&LogRecord::new(
+        LogLevel::Success,
+        "Login",
+        "User connected",
+    ))
+    .unwrap();
+
+    let mut buf = [0; 1024];
+    let len = listener.recv(&mut buf).unwrap();
+    let message = String::from_utf8_lossy(&buf[..len]);
+    assert!(message.starts_with("<13>1 "));
+    assert!(message.ends_with("[tlogger@32473 title=\"Login\"] User connected"));
+
+    std::fs::remove_file(&path).unwrap();
+}
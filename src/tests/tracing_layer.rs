@@ -0,0 +1,85 @@
+use tracing::Level;
+use tracing_subscriber::prelude::*;
+
+use crate::{format_console, prelude::*};
+
+#[test]
+fn tracing_levels_map_onto_tlogger_levels() {
+    assert_eq!(LogLevel::from(Level::ERROR), LogLevel::Error);
+    assert_eq!(LogLevel::from(Level::WARN), LogLevel::Warn);
+    assert_eq!(LogLevel::from(Level::INFO), LogLevel::Info);
+    assert_eq!(LogLevel::from(Level::DEBUG), LogLevel::Debug);
+    assert_eq!(LogLevel::from(Level::TRACE), LogLevel::Trace);
+}
+
+#[test]
+fn spans_and_events_become_records() {
+    let subscriber =
+        tracing_subscriber::registry().with(TLoggerLayer::new().with_box_level(Level::WARN));
+
+    let records = capture_logs(|| {
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("Outside of any span");
+
+            let request = tracing::info_span!("request", id = 7);
+            let _request = request.enter();
+            let db = tracing::debug_span!("db", table = "user_accounts");
+            let _db = db.enter();
+
+            tracing::info!(rows = 3, "Query finished");
+            tracing::warn!(user_id = 42, "Slow query on user_id a*b*c");
+        });
+    });
+
+    assert_eq!(records.len(), 3);
+    assert_logged!(records => Info, title = module_path!(), message = "Outside of any span");
+    assert!(!records[0].boxed);
+
+    let query = &records[1];
+    assert_eq!(query.level, LogLevel::Info);
+    assert_eq!(query.title, "request > db");
+    assert_eq!(query.message, "Query finished");
+    assert_eq!(
+        query.fields,
+        [
+            ("rows", "3".to_string()),
+            ("id", "7".to_string()),
+            ("table", "user_accounts".to_string()),
+        ]
+    );
+    assert_eq!(query.callsite.unwrap().module_path, module_path!());
+
+    // Messages are left alone, and warnings are boxed
+    let slow = &records[2];
+    assert_eq!(slow.level, LogLevel::Warn);
+    assert!(slow.raw && slow.boxed);
+    assert_eq!(slow.plain_message(), "Slow query on user_id a*b*c");
+    assert_eq!(slow.fields[0], ("user_id", "42".to_string()));
+    let console = strip_ansi_codes(&format_console(slow));
+    assert!(console.contains("Slow query on user_id a*b*c"));
+    assert!(console.contains("table   user_accounts"));
+}
+
+#[test]
+fn span_fields_become_record_fields() {
+    let subscriber = tracing_subscriber::registry().with(TLoggerLayer::new());
+
+    let records = capture_logs(|| {
+        tracing::subscriber::with_default(subscriber, || {
+            let request = tracing::info_span!("request", id = 7, user = tracing::field::Empty);
+            let _request = request.enter();
+            request.record("user", "Alice");
+
+            tracing::info!("Request handled");
+        });
+    });
+
+    // Span fields are never part of the message
+    assert_eq!(records[0].message, "Request handled");
+    assert_eq!(
+        records[0].fields,
+        [("id", "7".to_string()), ("user", "Alice".to_string())]
+    );
+    let file = LogFormat::Logfmt.format(&records[0]);
+    assert!(file.contains(r#"msg="Request handled" id=7 user=Alice"#));
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Utc};
+
+/// The time zone used for timestamps and for the boundaries of log intervals
+///
+/// # Example
+/// ```rust,no_run
+/// use tlogger::prelude::*;
+///
+/// init_logger("Logs", LogInterval::OneHour).unwrap();
+/// set_timezone(Timezone::Utc);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Timezone {
+    /// The time zone of the system, including its daylight saving time changes
+    #[default]
+    Local,
+    Utc,
+    /// A fixed offset from UTC, such as `FixedOffset::east_opt(9 * 3600)` for UTC+9
+    Fixed(FixedOffset),
+}
+
+impl Timezone {
+    /// Converts a point in time to this time zone
+    pub fn convert(&self, time: DateTime<Local>) -> DateTime<FixedOffset> {
+        match self {
+            Timezone::Local => time.fixed_offset(),
+            Timezone::Utc => time.with_timezone(&Utc).fixed_offset(),
+            Timezone::Fixed(offset) => time.with_timezone(offset),
+        }
+    }
+
+    /// The current time in this time zone
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.convert(Local::now())
+    }
+
+    /// The point in time a date and time in this time zone refers to
+    ///
+    /// When the local clock is turned back the earlier of the two is used, and
+    /// `None` is returned for local times skipped when the clock is turned forward.
+    pub(crate) fn resolve(&self, time: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Timezone::Local => time
+                .and_local_timezone(Local)
+                .earliest()
+                .map(|time| time.fixed_offset()),
+            Timezone::Utc => Some(time.and_utc().fixed_offset()),
+            Timezone::Fixed(offset) => time.and_local_timezone(*offset).single(),
+        }
+    }
+}
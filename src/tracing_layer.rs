@@ -0,0 +1,174 @@
+use std::fmt;
+
+use tracing_core::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use crate::{
+    fields::Fields,
+    log_record,
+    logger::LogLevel,
+    record::{Callsite, LogRecord},
+};
+
+/// Separator placed between span names in the title breadcrumb
+const BREADCRUMB_SEPARATOR: &str = " > ";
+
+/// A [`tracing_subscriber::Layer`] that renders events in the tlogger style.
+///
+/// Events are printed with the same layout as the tlogger macros. When the event
+/// happens inside spans, the span names are shown as a breadcrumb in the title
+/// (`request > db`) and the span fields are added after the event fields. Outside
+/// of any span the event target is used as the title. Messages are kept as is,
+/// `style_text!` markup is not applied to them.
+///
+/// Events are also written through the global `LOGGER`, so they follow the same
+/// log level filters as everything else.
+///
+/// # Example
+/// ```rust
+/// use tlogger::prelude::*;
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry()
+///     .with(TLoggerLayer::new().with_box_level(tracing::Level::ERROR))
+///     .init();
+///
+/// let span = tracing::info_span!("request", id = 7);
+/// let _guard = span.enter();
+/// tracing::info!(user = "Alice", "Logged in");
+/// ```
+#[derive(Default)]
+pub struct TLoggerLayer {
+    box_level: Option<Level>,
+}
+
+impl TLoggerLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render events at `level` or more severe with `create_styled_box`
+    ///
+    /// For example `Level::WARN` boxes warnings and errors.
+    pub fn with_box_level(mut self, level: Level) -> Self {
+        self.box_level = Some(level);
+        self
+    }
+
+    fn is_boxed(&self, level: &Level) -> bool {
+        self.box_level.is_some_and(|box_level| *level <= box_level)
+    }
+}
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::ERROR => LogLevel::Error,
+            Level::WARN => LogLevel::Warn,
+            Level::INFO => LogLevel::Info,
+            Level::DEBUG => LogLevel::Debug,
+            _ => LogLevel::Trace,
+        }
+    }
+}
+
+/// Fields of a span, stored in the span's extensions
+struct SpanFields(Fields);
+
+/// Collects the `message` field and any other fields
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Fields,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name(), format!("{:?}", value)));
+        }
+    }
+}
+
+impl<S> Layer<S> for TLoggerLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        span.extensions_mut().insert(SpanFields(visitor.fields));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+
+        let mut extensions = span.extensions_mut();
+        match extensions.get_mut::<SpanFields>() {
+            Some(SpanFields(fields)) => fields.append(&mut visitor.fields),
+            None => extensions.insert(SpanFields(visitor.fields)),
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let mut breadcrumb = Vec::new();
+        let mut fields = visitor.fields;
+
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                breadcrumb.push(span.name());
+
+                if let Some(SpanFields(span_fields)) = span.extensions().get::<SpanFields>() {
+                    fields.extend(span_fields.iter().cloned());
+                }
+            }
+        }
+
+        let title = if breadcrumb.is_empty() {
+            metadata.target().to_string()
+        } else {
+            breadcrumb.join(BREADCRUMB_SEPARATOR)
+        };
+
+        let mut record = LogRecord::new(LogLevel::from(*metadata.level()), title, visitor.message)
+            .with_fields(fields)
+            .boxed(self.is_boxed(metadata.level()))
+            .raw(true);
+
+        if let (Some(module_path), Some(file), Some(line)) =
+            (metadata.module_path(), metadata.file(), metadata.line())
+        {
+            record = record.with_callsite(Callsite::new(module_path, file, line));
+        }
+
+        log_record(&record);
+    }
+}